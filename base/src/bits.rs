@@ -0,0 +1,317 @@
+//! Bit-level [`BitWriter`] and [`BitReader`] primitives for bit-packed formats
+//!
+
+use core::fmt::Debug;
+
+use crate::Error;
+
+/// Bit ordering within each byte
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum BitOrder {
+    /// Most significant bit first, values are written from their MSB
+    Msb,
+    /// Least significant bit first, values are written from their LSB
+    Lsb,
+}
+
+/// Writer for fields at arbitrary bit offsets in a byte buffer
+#[derive(Debug)]
+pub struct BitWriter<'a> {
+    buff: &'a mut [u8],
+    order: BitOrder,
+    index: usize,
+}
+
+impl<'a> BitWriter<'a> {
+    /// Create a new [`BitWriter`] over the provided buffer
+    pub fn new(buff: &'a mut [u8], order: BitOrder) -> Self {
+        Self {
+            buff,
+            order,
+            index: 0,
+        }
+    }
+
+    /// Current write position in bits
+    pub fn position(&self) -> usize {
+        self.index
+    }
+
+    /// Remaining capacity in bits
+    pub fn remaining(&self) -> usize {
+        self.buff.len() * 8 - self.index
+    }
+
+    /// Write the lower `bits` bits of `value`
+    pub fn write(&mut self, value: u64, bits: usize) -> Result<(), Error> {
+        if bits > 64 {
+            return Err(Error::Overflow {
+                offset: self.index / 8,
+            });
+        }
+        if self.remaining() < bits {
+            return Err(Error::BufferTooSmall {
+                needed: (self.index + bits).div_ceil(8),
+                available: self.buff.len(),
+                offset: self.index / 8,
+            });
+        }
+
+        for i in 0..bits {
+            let bit = match self.order {
+                BitOrder::Msb => (value >> (bits - 1 - i)) & 1,
+                BitOrder::Lsb => (value >> i) & 1,
+            };
+
+            let (byte, mask) = bit_position(self.order, self.index);
+            match bit {
+                0 => self.buff[byte] &= !mask,
+                _ => self.buff[byte] |= mask,
+            }
+
+            self.index += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Write an [`EncodeBits`] object using `bits` bits
+    pub fn write_bits<T: EncodeBits>(&mut self, value: &T, bits: usize) -> Result<(), T::Error> {
+        value.encode_bits(self, bits)
+    }
+
+    /// Skip to the start of the next byte, clearing any unused bits
+    pub fn align(&mut self) -> Result<(), Error> {
+        let pad = (8 - self.index % 8) % 8;
+        self.write(0, pad)
+    }
+
+    /// Finish writing, returning the number of (partially) written bytes
+    pub fn finish(self) -> usize {
+        self.index.div_ceil(8)
+    }
+}
+
+/// Reader for fields at arbitrary bit offsets in a byte buffer
+#[derive(Clone, Debug)]
+pub struct BitReader<'a> {
+    buff: &'a [u8],
+    order: BitOrder,
+    index: usize,
+}
+
+impl<'a> BitReader<'a> {
+    /// Create a new [`BitReader`] over the provided buffer
+    pub fn new(buff: &'a [u8], order: BitOrder) -> Self {
+        Self {
+            buff,
+            order,
+            index: 0,
+        }
+    }
+
+    /// Current read position in bits
+    pub fn position(&self) -> usize {
+        self.index
+    }
+
+    /// Remaining bits available to read
+    pub fn remaining(&self) -> usize {
+        self.buff.len() * 8 - self.index
+    }
+
+    /// Read `bits` bits into the lower bits of a `u64`
+    pub fn read(&mut self, bits: usize) -> Result<u64, Error> {
+        if bits > 64 {
+            return Err(Error::Overflow {
+                offset: self.index / 8,
+            });
+        }
+        if self.remaining() < bits {
+            return Err(Error::Incomplete {
+                needed: (self.index + bits).div_ceil(8) - self.buff.len(),
+                offset: self.index / 8,
+            });
+        }
+
+        let mut value = 0u64;
+
+        for i in 0..bits {
+            let (byte, mask) = bit_position(self.order, self.index);
+            let bit = (self.buff[byte] & mask != 0) as u64;
+
+            match self.order {
+                BitOrder::Msb => value = (value << 1) | bit,
+                BitOrder::Lsb => value |= bit << i,
+            }
+
+            self.index += 1;
+        }
+
+        Ok(value)
+    }
+
+    /// Read a [`DecodeBits`] object from `bits` bits
+    pub fn read_bits<T: DecodeBits>(&mut self, bits: usize) -> Result<T, T::Error> {
+        T::decode_bits(self, bits)
+    }
+
+    /// Skip to the start of the next byte
+    pub fn align(&mut self) -> Result<(), Error> {
+        let pad = (8 - self.index % 8) % 8;
+        self.read(pad).map(|_| ())
+    }
+
+    /// Finish reading, returning the number of (partially) consumed bytes
+    pub fn finish(self) -> usize {
+        self.index.div_ceil(8)
+    }
+}
+
+/// Compute byte index and bit mask for a bit offset
+#[inline]
+fn bit_position(order: BitOrder, index: usize) -> (usize, u8) {
+    let mask = match order {
+        BitOrder::Msb => 0x80 >> (index % 8),
+        BitOrder::Lsb => 0x01 << (index % 8),
+    };
+    (index / 8, mask)
+}
+
+/// Encode trait for objects written at arbitrary bit widths
+pub trait EncodeBits: Debug {
+    /// Error type returned on encode error
+    type Error: From<Error> + Debug;
+
+    /// Write object to the provided [`BitWriter`] using `bits` bits
+    fn encode_bits(&self, w: &mut BitWriter, bits: usize) -> Result<(), Self::Error>;
+}
+
+/// Decode trait for objects read at arbitrary bit widths
+pub trait DecodeBits: Sized {
+    /// Error type returned on decode error
+    type Error: From<Error> + Debug;
+
+    /// Read object from the provided [`BitReader`] using `bits` bits
+    fn decode_bits(r: &mut BitReader, bits: usize) -> Result<Self, Self::Error>;
+}
+
+/// Helper macro for implementing bit encode / decode on unsigned integers
+macro_rules! impl_bits_unsigned {
+    ($t:ty) => {
+        impl EncodeBits for $t {
+            type Error = Error;
+
+            fn encode_bits(&self, w: &mut BitWriter, bits: usize) -> Result<(), Self::Error> {
+                // Check value fits in the requested width
+                if bits > <$t>::BITS as usize || (bits < 64 && (*self as u64) >> bits != 0) {
+                    return Err(Error::Overflow {
+                        offset: w.position() / 8,
+                    });
+                }
+
+                w.write(*self as u64, bits)
+            }
+        }
+
+        impl DecodeBits for $t {
+            type Error = Error;
+
+            fn decode_bits(r: &mut BitReader, bits: usize) -> Result<Self, Self::Error> {
+                if bits > <$t>::BITS as usize {
+                    return Err(Error::Overflow {
+                        offset: r.position() / 8,
+                    });
+                }
+
+                r.read(bits).map(|v| v as $t)
+            }
+        }
+    };
+}
+
+/// Helper macro for implementing bit encode / decode on signed (two's complement) integers
+macro_rules! impl_bits_signed {
+    ($t:ty) => {
+        impl EncodeBits for $t {
+            type Error = Error;
+
+            fn encode_bits(&self, w: &mut BitWriter, bits: usize) -> Result<(), Self::Error> {
+                if bits == 0 || bits > <$t>::BITS as usize {
+                    return Err(Error::Overflow {
+                        offset: w.position() / 8,
+                    });
+                }
+
+                // Check value fits in the requested width
+                let min = -1i64 << (bits - 1);
+                let max = !min;
+                if (*self as i64) < min || (*self as i64) > max {
+                    return Err(Error::Overflow {
+                        offset: w.position() / 8,
+                    });
+                }
+
+                let mask = u64::MAX >> (64 - bits);
+                w.write(*self as i64 as u64 & mask, bits)
+            }
+        }
+
+        impl DecodeBits for $t {
+            type Error = Error;
+
+            fn decode_bits(r: &mut BitReader, bits: usize) -> Result<Self, Self::Error> {
+                if bits == 0 || bits > <$t>::BITS as usize {
+                    return Err(Error::Overflow {
+                        offset: r.position() / 8,
+                    });
+                }
+
+                // Sign extend from the top decoded bit
+                let v = r.read(bits)?;
+                let shift = 64 - bits;
+                Ok(((v << shift) as i64 >> shift) as $t)
+            }
+        }
+    };
+}
+
+impl_bits_unsigned!(u8);
+impl_bits_unsigned!(u16);
+impl_bits_unsigned!(u32);
+impl_bits_unsigned!(u64);
+
+impl_bits_signed!(i8);
+impl_bits_signed!(i16);
+impl_bits_signed!(i32);
+impl_bits_signed!(i64);
+
+/// [`EncodeBits`] for `bool`, requires a width of 1 bit
+impl EncodeBits for bool {
+    type Error = Error;
+
+    fn encode_bits(&self, w: &mut BitWriter, bits: usize) -> Result<(), Self::Error> {
+        if bits != 1 {
+            return Err(Error::Overflow {
+                offset: w.position() / 8,
+            });
+        }
+
+        w.write(*self as u64, 1)
+    }
+}
+
+/// [`DecodeBits`] for `bool`, requires a width of 1 bit
+impl DecodeBits for bool {
+    type Error = Error;
+
+    fn decode_bits(r: &mut BitReader, bits: usize) -> Result<Self, Self::Error> {
+        if bits != 1 {
+            return Err(Error::Overflow {
+                offset: r.position() / 8,
+            });
+        }
+
+        r.read(1).map(|v| v != 0)
+    }
+}
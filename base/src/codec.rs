@@ -0,0 +1,333 @@
+//! [`tokio_util::codec`] adapter for encodable / decodable types, with pluggable framing
+
+use core::marker::PhantomData;
+
+use bytes::{Buf, BufMut, BytesMut};
+use num_traits::{AsPrimitive, FromPrimitive};
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::{
+    decode::{DecodeExt, DecodeOwned},
+    encode::{Encode, EncodeExt, EncodePrefixed},
+    io::IoError,
+    DecodeError, Error,
+};
+
+/// Maximum bytes reserved ahead of incomplete frames, bounding allocation
+/// to the data actually received rather than lengths claimed by the input
+const RESERVE_LIMIT: usize = 4096;
+
+/// Framing used by [`EncDecCodec`] to delimit encoded objects in a stream.
+///
+/// Decoding returns `Ok(None)` where more data is required to complete a frame,
+/// and an error only where data is malformed.
+pub trait Framing {
+    /// Decode an object from the head of the buffer, advancing past the frame on success
+    fn decode_frame<T>(&mut self, src: &mut BytesMut) -> Result<Option<T::Output>, T::Error>
+    where
+        T: DecodeOwned,
+        T::Error: DecodeError;
+
+    /// Encode an object as a frame, appending to the buffer
+    fn encode_frame<T: Encode>(&mut self, item: &T, dst: &mut BytesMut) -> Result<(), T::Error>;
+}
+
+/// Handle a decode result, returning `Ok(None)` and reserving space where input is incomplete
+fn incomplete<O, E: DecodeError>(
+    r: Result<(O, usize), E>,
+    src: &mut BytesMut,
+) -> Result<Option<O>, E> {
+    match r {
+        Ok((v, n)) => {
+            src.advance(n.min(src.len()));
+            Ok(Some(v))
+        }
+        Err(e) => match e.incomplete() {
+            Some(n) => {
+                src.reserve(n.min(RESERVE_LIMIT));
+                Ok(None)
+            }
+            None => Err(e),
+        },
+    }
+}
+
+/// Framing for self-delimiting objects (ie. fixed size or with internal
+/// length fields), decoded directly from the stream
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct SelfDelimited;
+
+impl Framing for SelfDelimited {
+    fn decode_frame<T>(&mut self, src: &mut BytesMut) -> Result<Option<T::Output>, T::Error>
+    where
+        T: DecodeOwned,
+        T::Error: DecodeError,
+    {
+        if src.is_empty() {
+            return Ok(None);
+        }
+
+        incomplete(T::decode_owned(src), src)
+    }
+
+    fn encode_frame<T: Encode>(&mut self, item: &T, dst: &mut BytesMut) -> Result<(), T::Error> {
+        dst.reserve(item.encode_len()?);
+        item.encode_buf(dst)?;
+        Ok(())
+    }
+}
+
+/// Framing for fixed size frames, where each object must occupy exactly `N` bytes
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct FixedSize<const N: usize>;
+
+impl<const N: usize> Framing for FixedSize<N> {
+    fn decode_frame<T>(&mut self, src: &mut BytesMut) -> Result<Option<T::Output>, T::Error>
+    where
+        T: DecodeOwned,
+        T::Error: DecodeError,
+    {
+        if src.len() < N {
+            src.reserve(N - src.len());
+            return Ok(None);
+        }
+
+        let v = T::decode_exact(&src[..N])?;
+        src.advance(N);
+
+        Ok(Some(v))
+    }
+
+    fn encode_frame<T: Encode>(&mut self, item: &T, dst: &mut BytesMut) -> Result<(), T::Error> {
+        // Objects must fill the frame exactly, larger objects can not fit
+        // while smaller objects would not decode as a whole frame
+        let n = item.encode_len()?;
+        if n > N {
+            return Err(Error::BufferTooSmall {
+                needed: n,
+                available: N,
+                offset: 0,
+            }
+            .into());
+        } else if n < N {
+            return Err(Error::InvalidValue { offset: 0 }.into());
+        }
+
+        dst.reserve(N);
+        item.encode_buf(dst)?;
+
+        Ok(())
+    }
+}
+
+/// Framing for length-prefixed frames, using a prefix of type `P`
+/// matching the layout of [`EncodePrefixed`]
+#[derive(Debug)]
+pub struct LengthPrefixed<P> {
+    _p: PhantomData<fn() -> P>,
+}
+
+impl<P> LengthPrefixed<P> {
+    /// Create a new [`LengthPrefixed`] framing
+    pub fn new() -> Self {
+        Self { _p: PhantomData }
+    }
+}
+
+impl<P> Clone for LengthPrefixed<P> {
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
+impl<P> Default for LengthPrefixed<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P> Framing for LengthPrefixed<P>
+where
+    P: DecodeOwned<Output = P, Error = Error> + Encode<Error = Error>,
+    P: FromPrimitive + AsPrimitive<usize>,
+{
+    fn decode_frame<T>(&mut self, src: &mut BytesMut) -> Result<Option<T::Output>, T::Error>
+    where
+        T: DecodeOwned,
+        T::Error: DecodeError,
+    {
+        // Decode length prefix
+        let (len, n): (usize, _) = match P::decode_owned(src) {
+            Ok((len, n)) => (len.as_(), n),
+            Err(e) if e.incomplete().is_some() => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        // Wait for the complete frame
+        let end = n.saturating_add(len);
+        if src.len() < end {
+            src.reserve((end - src.len()).min(RESERVE_LIMIT));
+            return Ok(None);
+        }
+
+        // Consume the frame regardless of decode result to resynchronise,
+        // as the frame is complete any decode error indicates malformed data
+        let frame = src.split_to(end);
+        let v = T::decode_exact(&frame[n..])?;
+
+        Ok(Some(v))
+    }
+
+    fn encode_frame<T: Encode>(&mut self, item: &T, dst: &mut BytesMut) -> Result<(), T::Error> {
+        let n = item.encode_len()?;
+        let len = match P::from_usize(n) {
+            Some(v) => v,
+            None => return Err(Error::Overflow { offset: 0 }.into()),
+        };
+
+        // Encode prefix and object in place
+        let start = dst.len();
+        dst.resize(start + len.encode_len()? + n, 0);
+
+        match <T as EncodePrefixed<P>>::encode_prefixed(item, &mut dst[start..]) {
+            Ok(n) => {
+                dst.truncate(start + n);
+                Ok(())
+            }
+            Err(e) => {
+                dst.truncate(start);
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Framing for delimiter terminated frames, where objects must not contain the delimiter
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Delimited {
+    delimiter: u8,
+}
+
+impl Delimited {
+    /// Create a new [`Delimited`] framing with the provided delimiter
+    pub fn new(delimiter: u8) -> Self {
+        Self { delimiter }
+    }
+}
+
+impl Framing for Delimited {
+    fn decode_frame<T>(&mut self, src: &mut BytesMut) -> Result<Option<T::Output>, T::Error>
+    where
+        T: DecodeOwned,
+        T::Error: DecodeError,
+    {
+        let end = match src.iter().position(|b| *b == self.delimiter) {
+            Some(n) => n,
+            None => return Ok(None),
+        };
+
+        // Consume the frame regardless of decode result to resynchronise
+        let frame = src.split_to(end + 1);
+        let v = T::decode_exact(&frame[..end])?;
+
+        Ok(Some(v))
+    }
+
+    fn encode_frame<T: Encode>(&mut self, item: &T, dst: &mut BytesMut) -> Result<(), T::Error> {
+        let start = dst.len();
+        SelfDelimited.encode_frame(item, dst)?;
+
+        if let Some(n) = dst[start..].iter().position(|b| *b == self.delimiter) {
+            dst.truncate(start);
+            return Err(Error::InvalidValue { offset: n }.into());
+        }
+
+        dst.put_u8(self.delimiter);
+
+        Ok(())
+    }
+}
+
+/// [`tokio_util::codec`] adapter for types implementing [`Encode`] and [`DecodeOwned`]
+///
+/// Incomplete frames are buffered until complete, with errors returned only
+/// for malformed data.
+///
+/// ```
+/// use bytes::BytesMut;
+/// use tokio_util::codec::{Decoder, Encoder};
+/// use encdec_base::codec::{EncDecCodec, LengthPrefixed};
+///
+/// let mut codec = EncDecCodec::<u32, _>::with_framing(LengthPrefixed::<u8>::new());
+///
+/// let mut buff = BytesMut::new();
+/// codec.encode(0x01020304, &mut buff).unwrap();
+/// assert_eq!(&buff[..], &[4, 4, 3, 2, 1]);
+///
+/// let mut partial = buff.split_to(3);
+/// assert_eq!(codec.decode(&mut partial).unwrap(), None);
+///
+/// partial.unsplit(buff);
+/// assert_eq!(codec.decode(&mut partial).unwrap(), Some(0x01020304));
+/// ```
+#[derive(Debug)]
+pub struct EncDecCodec<T, F = SelfDelimited> {
+    framing: F,
+    _t: PhantomData<fn() -> T>,
+}
+
+impl<T> EncDecCodec<T, SelfDelimited> {
+    /// Create a new [`EncDecCodec`] for self-delimiting objects
+    pub fn new() -> Self {
+        Self::with_framing(SelfDelimited)
+    }
+}
+
+impl<T> Default for EncDecCodec<T, SelfDelimited> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, F: Framing> EncDecCodec<T, F> {
+    /// Create a new [`EncDecCodec`] with the provided [`Framing`]
+    pub fn with_framing(framing: F) -> Self {
+        Self {
+            framing,
+            _t: PhantomData,
+        }
+    }
+
+    /// Fetch the [`Framing`] used by the codec
+    pub fn framing(&self) -> &F {
+        &self.framing
+    }
+}
+
+impl<T, F> Decoder for EncDecCodec<T, F>
+where
+    T: DecodeOwned<Output = T>,
+    T::Error: DecodeError,
+    F: Framing,
+{
+    type Item = T;
+    type Error = IoError<<T as DecodeOwned>::Error>;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        self.framing.decode_frame::<T>(src).map_err(IoError::Codec)
+    }
+}
+
+impl<T, F> Encoder<T> for EncDecCodec<T, F>
+where
+    T: Encode,
+    F: Framing,
+{
+    type Error = IoError<<T as Encode>::Error>;
+
+    fn encode(&mut self, item: T, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        self.framing
+            .encode_frame(&item, dst)
+            .map_err(IoError::Codec)
+    }
+}
@@ -0,0 +1,186 @@
+//! Zero-copy [`Decode`] implementations for borrowed views into buffers
+//!
+
+use core::fmt::Debug;
+
+use super::{Decode, DecodeOwned, DecodedTagged};
+use crate::{
+    encode::{FixedEncodedLen, MaxEncodedLen},
+    Encode, Error,
+};
+
+/// Marker trait for plain-old-data types that may be borrowed directly
+/// from an encoded buffer without copying.
+///
+/// # Safety
+///
+/// Implementors must:
+/// - have an alignment of 1 (ie. be composed of `u8` / `i8` or arrays / `#[repr(transparent)]` wrappers thereof,
+///   see [`U16Le`] and friends for multi-byte primitives)
+/// - have a non-zero size with no padding bytes
+/// - be valid for any bit pattern
+/// - match the encoded (little-endian) layout byte-for-byte
+///
+/// As [`Decode`] is implemented for owned types, single objects are borrowed
+/// via [`Pod::decode_ref`] rather than a `Decode` impl on `&'a T`.
+pub unsafe trait Pod: Sized + Debug {
+    /// Borrow an object from the start of the provided buffer,
+    /// returning a reference and the decoded length
+    fn decode_ref(buff: &[u8]) -> Result<(&Self, usize), Error> {
+        let n = core::mem::size_of::<Self>();
+        if buff.len() < n {
+            return Err(Error::Incomplete {
+                needed: n - buff.len(),
+                offset: 0,
+            });
+        }
+
+        let v = cast_slice::<Self>(&buff[..n])?;
+
+        Ok((&v[0], n))
+    }
+}
+
+unsafe impl Pod for u8 {}
+unsafe impl Pod for i8 {}
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
+/// Helper macro for little-endian [`Pod`] wrappers over multi-byte primitives
+macro_rules! impl_le {
+    ($($name:ident: $t:ty),*) => {
+        $(
+            #[doc = concat!("Little-endian `", stringify!($t), "` stored as bytes, allowing zero-copy")]
+            #[doc = concat!("views of `", stringify!($t), "` data (ie. `&'a [", stringify!($name), "]`).")]
+            #[derive(Copy, Clone, PartialEq, Eq, Hash, Default)]
+            #[repr(transparent)]
+            pub struct $name([u8; core::mem::size_of::<$t>()]);
+
+            impl $name {
+                #[doc = concat!("Create a new [`", stringify!($name), "`] from a native value")]
+                pub fn new(v: $t) -> Self {
+                    Self(v.to_le_bytes())
+                }
+
+                /// Fetch the native value
+                pub fn get(&self) -> $t {
+                    <$t>::from_le_bytes(self.0)
+                }
+            }
+
+            impl From<$t> for $name {
+                fn from(v: $t) -> Self {
+                    Self::new(v)
+                }
+            }
+
+            impl From<$name> for $t {
+                fn from(v: $name) -> Self {
+                    v.get()
+                }
+            }
+
+            impl Debug for $name {
+                fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    Debug::fmt(&self.get(), f)
+                }
+            }
+
+            // Safety: byte array wrapper with alignment 1, no padding and no invalid bit patterns
+            unsafe impl Pod for $name {}
+
+            impl Encode for $name {
+                type Error = Error;
+
+                fn encode_len(&self) -> Result<usize, Self::Error> {
+                    Ok(self.0.len())
+                }
+
+                fn encode(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
+                    self.0.encode(buff)
+                }
+            }
+
+            impl DecodeOwned for $name {
+                type Output = Self;
+                type Error = Error;
+
+                fn decode_owned(buff: &[u8]) -> Result<(Self::Output, usize), Self::Error> {
+                    let (v, n) = <[u8; core::mem::size_of::<$t>()]>::decode_owned(buff)?;
+                    Ok((Self(v), n))
+                }
+            }
+
+            impl MaxEncodedLen for $name {
+                const MAX_ENCODED_LEN: usize = core::mem::size_of::<$t>();
+            }
+
+            impl FixedEncodedLen for $name {
+                const ENCODED_LEN: usize = core::mem::size_of::<$t>();
+            }
+        )*
+    };
+}
+
+impl_le!(U16Le: u16, I16Le: i16, U32Le: u32, I32Le: i32, U64Le: u64, I64Le: i64, F32Le: f32, F64Le: f64);
+
+/// Reinterpret a byte slice as a slice of [`Pod`] objects
+fn cast_slice<T: Pod>(buff: &[u8]) -> Result<&[T], Error> {
+    let size = core::mem::size_of::<T>();
+
+    // Avoids `usize::is_multiple_of` (Rust 1.87) to retain support for older toolchains
+    #[allow(clippy::manual_is_multiple_of)]
+    if core::mem::align_of::<T>() != 1 || size == 0 || buff.len() % size != 0 {
+        return Err(Error::InvalidValue { offset: 0 });
+    }
+
+    // Safety: alignment, size and validity are guaranteed by the [`Pod`] bound
+    // and the checks above
+    let v = unsafe { core::slice::from_raw_parts(buff.as_ptr() as *const T, buff.len() / size) };
+
+    Ok(v)
+}
+
+/// Zero-copy [`Decode`] for references to fixed size arrays of [`Pod`] types (including `&[u8; N]`)
+impl<'a, T: Pod, const N: usize> Decode<'a> for &'a [T; N] {
+    type Output = &'a [T; N];
+    type Error = Error;
+
+    fn decode(buff: &'a [u8]) -> Result<(Self::Output, usize), Self::Error> {
+        let n = core::mem::size_of::<[T; N]>();
+        if buff.len() < n {
+            return Err(Error::Incomplete {
+                needed: n - buff.len(),
+                offset: 0,
+            });
+        }
+
+        let v = cast_slice::<T>(&buff[..n])?;
+
+        match v.try_into() {
+            Ok(v) => Ok((v, n)),
+            Err(_) => Err(Error::InvalidValue { offset: 0 }),
+        }
+    }
+}
+
+/// Zero-copy [`DecodedTagged`] impl for slices of [`Pod`] types (including `&[u8]`)
+/// (requires `#[encdec(length=...)]` length delimiter, in bytes)
+///
+/// This replaces the previous `&[u8]`-only impl with identical output for byte slices.
+/// Short buffers now return [`Error::Incomplete`] rather than [`Error::Length`],
+/// and slices of other types are only decodable where the element type is [`Pod`].
+impl<'a, T: Pod + 'a> DecodedTagged<'a> for &[T] {
+    type Output = &'a [T];
+    type Error = Error;
+
+    fn decode_len(buff: &'a [u8], len: usize) -> Result<Self::Output, Self::Error> {
+        if buff.len() < len {
+            return Err(Error::Incomplete {
+                needed: len - buff.len(),
+                offset: 0,
+            });
+        }
+
+        cast_slice(&buff[..len])
+    }
+}
@@ -0,0 +1,122 @@
+//! Cursor for reading sequences of objects from an input buffer
+
+use super::{Decode, DecodeLimits, DecodedTagged};
+use crate::Error;
+
+/// Cursor over an input buffer, tracking the read position for
+/// hand-written (and derived) [`Decode`] implementations.
+///
+/// Errors returned by nested objects are relative to the cursor
+/// [`position`][DecodeCursor::position] at the time of the call.
+/// Reads never index out of bounds, where an object reports a length exceeding
+/// the input subsequent reads see an empty buffer.
+///
+/// ```
+/// use encdec_base::decode::DecodeCursor;
+///
+/// let data = [0x10, 0x00, 0xcd, 0xab, 0x01];
+/// let mut c = DecodeCursor::new(&data);
+///
+/// assert_eq!(c.read::<u8>(), Ok(0x10));
+/// c.skip(1).unwrap();
+/// assert_eq!(c.peek::<u16>(), Ok(0xabcd));
+/// assert_eq!(c.read::<u16>(), Ok(0xabcd));
+///
+/// assert_eq!(c.position(), 4);
+/// assert_eq!(c.remaining(), 1);
+/// assert_eq!(c.rest(), &[0x01]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct DecodeCursor<'a> {
+    buff: &'a [u8],
+    index: usize,
+}
+
+impl<'a> DecodeCursor<'a> {
+    /// Create a new [`DecodeCursor`] reading from the start of the provided buffer
+    pub fn new(buff: &'a [u8]) -> Self {
+        Self { buff, index: 0 }
+    }
+
+    /// Decode an object at the current position, advancing past the decoded object
+    pub fn read<T: Decode<'a>>(&mut self) -> Result<T::Output, T::Error> {
+        self.read_with(T::decode)
+    }
+
+    /// Decode an object at the current position with the provided [`DecodeLimits`],
+    /// advancing past the decoded object
+    pub fn read_limited<T: Decode<'a>>(
+        &mut self,
+        limits: &mut DecodeLimits,
+    ) -> Result<T::Output, T::Error> {
+        self.read_with(|b| T::decode_limited(b, limits))
+    }
+
+    /// Decode an externally length-tagged object of `len` bytes at the current position,
+    /// advancing past the object
+    pub fn read_len<T: DecodedTagged<'a>>(&mut self, len: usize) -> Result<T::Output, T::Error> {
+        self.read_len_limited::<T>(len, &mut DecodeLimits::new())
+    }
+
+    /// Decode an externally length-tagged object of `len` bytes at the current position
+    /// with the provided [`DecodeLimits`], advancing past the object
+    pub fn read_len_limited<T: DecodedTagged<'a>>(
+        &mut self,
+        len: usize,
+        limits: &mut DecodeLimits,
+    ) -> Result<T::Output, T::Error> {
+        let v = T::decode_len_limited(self.rest(), len, limits)?;
+        self.index = self.index.saturating_add(len);
+        Ok(v)
+    }
+
+    /// Read at the current position using the provided decode function,
+    /// advancing by the returned length
+    pub fn read_with<O, E>(
+        &mut self,
+        f: impl FnOnce(&'a [u8]) -> Result<(O, usize), E>,
+    ) -> Result<O, E> {
+        let (v, n) = f(self.rest())?;
+        self.index = self.index.saturating_add(n);
+        Ok(v)
+    }
+
+    /// Decode an object at the current position without advancing
+    pub fn peek<T: Decode<'a>>(&self) -> Result<T::Output, T::Error> {
+        T::decode(self.rest()).map(|(v, _n)| v)
+    }
+
+    /// Skip `n` bytes of input
+    pub fn skip(&mut self, n: usize) -> Result<(), Error> {
+        if n > self.remaining() {
+            return Err(Error::Incomplete {
+                needed: n - self.remaining(),
+                offset: self.index,
+            });
+        }
+
+        self.index += n;
+
+        Ok(())
+    }
+
+    /// Fetch the current read position (and number of bytes consumed)
+    pub fn position(&self) -> usize {
+        self.index
+    }
+
+    /// Fetch the number of bytes remaining in the buffer
+    pub fn remaining(&self) -> usize {
+        self.buff.len().saturating_sub(self.index)
+    }
+
+    /// Check whether the input has been fully consumed
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Fetch the remaining (unread) input
+    pub fn rest(&self) -> &'a [u8] {
+        self.buff.get(self.index..).unwrap_or_default()
+    }
+}
@@ -1,6 +1,6 @@
 use core::{fmt::Debug, marker::PhantomData};
 
-use super::Decode;
+use super::{Decode, DecodeOwned};
 use crate::Error;
 
 /// Extensions to [`Decode`] trait for decodable objects
@@ -15,11 +15,82 @@ pub trait DecodeExt<'a>: Decode<'a> {
             _t: PhantomData,
         }
     }
+
+    /// Decode an object that must consume the whole buffer,
+    /// returning [`Error::TrailingBytes`] where bytes remain
+    fn decode_exact(buff: &'a [u8]) -> Result<Self::Output, Self::Error> {
+        let (v, n) = Self::decode(buff)?;
+
+        match n < buff.len() {
+            true => Err(Error::TrailingBytes {
+                remaining: buff.len() - n,
+                offset: n,
+            }
+            .into()),
+            false => Ok(v),
+        }
+    }
 }
 
 /// Blanket implementation for all [`Decode`] types
 impl<'a, T: Decode<'a>> DecodeExt<'a> for T {}
 
+/// Extensions to [`DecodeOwned`] trait for owned decodable objects
+pub trait DecodeOwnedExt: DecodeOwned {
+    /// Decode an owned object that must consume the whole buffer,
+    /// returning [`Error::TrailingBytes`] where bytes remain
+    fn decode_owned_exact(buff: &[u8]) -> Result<Self::Output, Self::Error> {
+        let (v, n) = Self::decode_owned(buff)?;
+
+        match n < buff.len() {
+            true => Err(Error::TrailingBytes {
+                remaining: buff.len() - n,
+                offset: n,
+            }
+            .into()),
+            false => Ok(v),
+        }
+    }
+
+    /// Decode an owned object from a [`std::io::Read`]er, reading only the bytes
+    /// required for the object.
+    ///
+    /// Objects must be self-delimiting (ie. fixed size or length prefixed), with
+    /// incomplete input reported via [`DecodeError::incomplete`][crate::DecodeError].
+    #[cfg(feature = "std")]
+    fn decode_from_reader(
+        r: &mut impl std::io::Read,
+    ) -> Result<Self::Output, crate::io::IoError<Self::Error>>
+    where
+        Self::Error: crate::DecodeError,
+    {
+        use crate::{io::IoError, DecodeError};
+
+        let mut buff = alloc::vec::Vec::new();
+
+        loop {
+            let e = match Self::decode_owned(&buff) {
+                Ok((v, _n)) => return Ok(v),
+                Err(e) => e,
+            };
+
+            // Read additional data where required, bounding each read so
+            // allocation follows the received data
+            let needed = match e.incomplete() {
+                Some(n) => n.clamp(1, crate::io::READ_CHUNK),
+                None => return Err(IoError::Codec(e)),
+            };
+
+            let offset = buff.len();
+            buff.resize(offset + needed, 0);
+            r.read_exact(&mut buff[offset..])?;
+        }
+    }
+}
+
+/// Blanket implementation for all [`DecodeOwned`] types
+impl<T: DecodeOwned> DecodeOwnedExt for T {}
+
 /// Helper type for parsing lists of decodable objects (with internal length delimiters)
 #[derive(Debug)]
 pub struct DecodeIter<'a, T> {
@@ -54,7 +125,7 @@ where
     /// Decode and fetch the next item
     fn next(&mut self) -> Option<Self::Item> {
         // Exit on buffer exhaustion
-        if self.index == self.buff.len() {
+        if self.index >= self.buff.len() {
             return None;
         }
 
@@ -64,6 +135,14 @@ where
             Err(e) => return Some(Err(e)),
         };
 
+        // Zero-length objects can not consume the buffer, stop iterating
+        // rather than returning the same object indefinitely
+        if n == 0 {
+            let offset = self.index;
+            self.index = self.buff.len();
+            return Some(Err(Error::InvalidValue { offset }.into()));
+        }
+
         // Increment the index
         self.index += n;
 
@@ -0,0 +1,195 @@
+//! Configurable limits for decoding untrusted input
+
+use core::ops::{Deref, DerefMut};
+
+use crate::{Error, Limit};
+
+/// Limits applied while decoding, bounding resource use for untrusted input.
+///
+/// Limits are passed to [`Decode::decode_limited`][super::Decode::decode_limited]
+/// (or [`DecodeOwned::decode_owned_limited`][super::DecodeOwned::decode_owned_limited])
+/// and enforced by the built-in collection impls and derived decoders,
+/// returning [`Error::LimitExceeded`] when exceeded.
+///
+/// The default configuration is unlimited, matching the behaviour of
+/// [`Decode::decode`][super::Decode::decode].
+///
+/// Nesting depth is restored on exit from each object whether or not decoding succeeds,
+/// so limits may be reused following a failed decode. Allocations are cumulative over all
+/// decodes sharing a [`DecodeLimits`] instance, bounding the total memory allocated for a
+/// session, see [`DecodeLimits::reset`] to start a new allocation budget.
+///
+/// ```
+/// # #[cfg(feature = "alloc")]
+/// # fn main() {
+/// use encdec_base::{decode::{DecodeLimits, DecodeOwned}, Error, Limit};
+///
+/// let mut limits = DecodeLimits::new().with_max_elements(2);
+///
+/// let r = Vec::<u8>::decode_owned_limited(&[1, 2, 3], &mut limits);
+/// assert_eq!(r, Err(Error::LimitExceeded{ limit: Limit::Elements, offset: 2 }));
+/// # }
+/// # #[cfg(not(feature = "alloc"))]
+/// # fn main() {}
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct DecodeLimits {
+    max_elements: usize,
+    max_alloc: usize,
+    max_depth: usize,
+    max_str_len: usize,
+
+    depth: usize,
+    allocated: usize,
+}
+
+impl DecodeLimits {
+    /// Create a new (unlimited) [`DecodeLimits`] instance
+    pub const fn new() -> Self {
+        Self {
+            max_elements: usize::MAX,
+            max_alloc: usize::MAX,
+            max_depth: usize::MAX,
+            max_str_len: usize::MAX,
+            depth: 0,
+            allocated: 0,
+        }
+    }
+
+    /// Set the maximum number of elements in a single collection
+    pub const fn with_max_elements(mut self, n: usize) -> Self {
+        self.max_elements = n;
+        self
+    }
+
+    /// Set the maximum bytes allocated over all decode operations using these limits
+    pub const fn with_max_alloc(mut self, n: usize) -> Self {
+        self.max_alloc = n;
+        self
+    }
+
+    /// Set the maximum nesting depth of derived objects and collections
+    pub const fn with_max_depth(mut self, n: usize) -> Self {
+        self.max_depth = n;
+        self
+    }
+
+    /// Set the maximum length of decoded strings in bytes
+    pub const fn with_max_str_len(mut self, n: usize) -> Self {
+        self.max_str_len = n;
+        self
+    }
+
+    /// Fetch the current nesting depth
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Fetch the bytes allocated so far
+    pub fn allocated(&self) -> usize {
+        self.allocated
+    }
+
+    /// Reset depth and allocation counters, retaining configured limits
+    pub fn reset(&mut self) {
+        self.depth = 0;
+        self.allocated = 0;
+    }
+
+    /// Enter a nested object, checking the depth limit
+    pub fn enter(&mut self) -> Result<(), Error> {
+        if self.depth >= self.max_depth {
+            return Err(Error::LimitExceeded {
+                limit: Limit::Depth,
+                offset: 0,
+            });
+        }
+
+        self.depth += 1;
+
+        Ok(())
+    }
+
+    /// Exit a nested object
+    pub fn exit(&mut self) {
+        self.depth = self.depth.saturating_sub(1);
+    }
+
+    /// Enter a nested object, checking the depth limit and returning a [`LimitScope`]
+    /// guard which exits the object on drop whether or not decoding succeeds
+    pub fn scope(&mut self) -> Result<LimitScope<'_>, Error> {
+        self.enter()?;
+        Ok(LimitScope { limits: self })
+    }
+
+    /// Check a collection element count against the element limit
+    pub fn elements(&self, count: usize, offset: usize) -> Result<(), Error> {
+        match count > self.max_elements {
+            true => Err(Error::LimitExceeded {
+                limit: Limit::Elements,
+                offset,
+            }),
+            false => Ok(()),
+        }
+    }
+
+    /// Record an allocation of `bytes`, checking the allocation limit
+    pub fn alloc(&mut self, bytes: usize, offset: usize) -> Result<(), Error> {
+        let allocated = self.allocated.saturating_add(bytes);
+
+        if allocated > self.max_alloc {
+            return Err(Error::LimitExceeded {
+                limit: Limit::Alloc,
+                offset,
+            });
+        }
+
+        self.allocated = allocated;
+
+        Ok(())
+    }
+
+    /// Check a string length against the string length limit
+    pub fn str_len(&self, len: usize, offset: usize) -> Result<(), Error> {
+        match len > self.max_str_len {
+            true => Err(Error::LimitExceeded {
+                limit: Limit::StrLen,
+                offset,
+            }),
+            false => Ok(()),
+        }
+    }
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Guard for a nested object entered with [`DecodeLimits::scope`], dereferencing
+/// to the underlying [`DecodeLimits`] and exiting the object on drop
+#[derive(Debug)]
+pub struct LimitScope<'a> {
+    limits: &'a mut DecodeLimits,
+}
+
+impl Deref for LimitScope<'_> {
+    type Target = DecodeLimits;
+
+    fn deref(&self) -> &Self::Target {
+        self.limits
+    }
+}
+
+impl DerefMut for LimitScope<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.limits
+    }
+}
+
+impl Drop for LimitScope<'_> {
+    fn drop(&mut self) {
+        self.limits.exit();
+    }
+}
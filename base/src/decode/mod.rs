@@ -8,7 +8,7 @@ mod owned;
 pub use owned::DecodeOwned;
 
 mod ext;
-pub use ext::{DecodeExt, DecodeIter};
+pub use ext::{DecodeExt, DecodeIter, DecodeOwnedExt};
 
 mod tagged;
 pub use tagged::DecodedTagged;
@@ -16,7 +16,32 @@ pub use tagged::DecodedTagged;
 mod prefixed;
 pub use prefixed::DecodePrefixed;
 
+mod borrowed;
+pub use borrowed::{F32Le, F64Le, I16Le, I32Le, I64Le, Pod, U16Le, U32Le, U64Le};
+
+mod cursor;
+pub use cursor::DecodeCursor;
+
+mod limits;
+pub use limits::{DecodeLimits, LimitScope};
+
+mod stream;
+pub use stream::{DecodeStatus, StreamBuffer, StreamDecoder};
+
+mod split;
+pub use split::{
+    decode_scratch, decode_split, decode_split_owned, DecodeSplit, Scratch, SplitBuf, SPLIT_SCRATCH,
+};
+
+#[cfg(feature = "bytes")]
+mod shared;
+#[cfg(feature = "bytes")]
+pub use shared::DecodeBytes;
+
 /// Decode trait implemented for binary decodable objects
+///
+/// Implementations provided by `encdec` (and those generated by `#[derive(Decode)]`)
+/// do not panic on short or malformed input, returning an [`Error`] instead.
 pub trait Decode<'a>: Sized {
     /// Output type (allows attaching lifetime bounds where required)
     type Output: Debug;
@@ -26,4 +51,16 @@ pub trait Decode<'a>: Sized {
 
     /// Decode consumes a slice and returns an object and decoded length.
     fn decode(buff: &'a [u8]) -> Result<(Self::Output, usize), Self::Error>;
+
+    /// Decode with the provided [`DecodeLimits`], for untrusted input.
+    ///
+    /// Defaults to [`Decode::decode`], implementations containing collections
+    /// or nested objects should override this to propagate limits.
+    fn decode_limited(
+        buff: &'a [u8],
+        limits: &mut DecodeLimits,
+    ) -> Result<(Self::Output, usize), Self::Error> {
+        let _ = limits;
+        Self::decode(buff)
+    }
 }
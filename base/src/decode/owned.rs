@@ -1,6 +1,6 @@
-use core::fmt::Debug;
+use core::{fmt::Debug, marker::PhantomData};
 
-use super::Decode;
+use super::{Decode, DecodeLimits};
 use crate::Error;
 
 /// Decode trait implemented for owned types
@@ -15,6 +15,17 @@ pub trait DecodeOwned {
 
     /// Decode consumes a slice and returns an object and decoded length.
     fn decode_owned(buff: &[u8]) -> Result<(Self::Output, usize), Self::Error>;
+
+    /// Decode with the provided [`DecodeLimits`], for untrusted input.
+    ///
+    /// Defaults to [`DecodeOwned::decode_owned`], see [`Decode::decode_limited`].
+    fn decode_owned_limited(
+        buff: &[u8],
+        limits: &mut DecodeLimits,
+    ) -> Result<(Self::Output, usize), Self::Error> {
+        let _ = limits;
+        Self::decode_owned(buff)
+    }
 }
 
 /// Blanket [`Decode`] impl for [`DecodeOwned`] types
@@ -26,6 +37,35 @@ impl<'a, T: DecodeOwned> Decode<'a> for T {
     fn decode(buff: &'a [u8]) -> Result<(Self::Output, usize), Self::Error> {
         <T as DecodeOwned>::decode_owned(buff)
     }
+
+    fn decode_limited(
+        buff: &'a [u8],
+        limits: &mut DecodeLimits,
+    ) -> Result<(Self::Output, usize), Self::Error> {
+        <T as DecodeOwned>::decode_owned_limited(buff, limits)
+    }
+}
+
+/// Zero-length [`DecodeOwned`] implementation for unit types
+impl DecodeOwned for () {
+    type Output = ();
+    type Error = Error;
+
+    #[inline]
+    fn decode_owned(_buff: &[u8]) -> Result<(Self::Output, usize), Self::Error> {
+        Ok(((), 0))
+    }
+}
+
+/// Zero-length [`DecodeOwned`] implementation for [`PhantomData`] markers
+impl<T: ?Sized> DecodeOwned for PhantomData<T> {
+    type Output = PhantomData<T>;
+    type Error = Error;
+
+    #[inline]
+    fn decode_owned(_buff: &[u8]) -> Result<(Self::Output, usize), Self::Error> {
+        Ok((PhantomData, 0))
+    }
 }
 
 #[cfg(not(feature = "nightly"))]
@@ -38,11 +78,19 @@ where
     type Output = [<T as DecodeOwned>::Output; N];
 
     fn decode_owned(buff: &[u8]) -> Result<(Self::Output, usize), Self::Error> {
+        Self::decode_owned_limited(buff, &mut DecodeLimits::new())
+    }
+
+    fn decode_owned_limited(
+        buff: &[u8],
+        limits: &mut DecodeLimits,
+    ) -> Result<(Self::Output, usize), Self::Error> {
         let mut data: [T; N] = [T::default(); N];
 
         let mut offset = 0;
         for value in data.iter_mut() {
-            let (output, length) = T::decode_owned(&buff[offset..])?;
+            let (output, length) =
+                T::decode_owned_limited(buff.get(offset..).unwrap_or_default(), limits)?;
             offset += length;
             *value = output;
         }
@@ -63,14 +111,23 @@ where
     type Output = [<T as DecodeOwned>::Output; N];
 
     fn decode_owned(buff: &[u8]) -> Result<(Self::Output, usize), Self::Error> {
+        Self::decode_owned_limited(buff, &mut DecodeLimits::new())
+    }
+
+    fn decode_owned_limited(
+        buff: &[u8],
+        limits: &mut DecodeLimits,
+    ) -> Result<(Self::Output, usize), Self::Error> {
         let mut index = 0;
 
-        let decoded = core::array::try_from_fn(|_i| match T::decode(&buff[index..]) {
-            Ok((o, l)) => {
-                index += l;
-                Ok(o)
+        let decoded = core::array::try_from_fn(|_i| {
+            match T::decode_owned_limited(buff.get(index..).unwrap_or_default(), limits) {
+                Ok((o, l)) => {
+                    index += l;
+                    Ok(o)
+                }
+                Err(e) => Err(e),
             }
-            Err(e) => Err(e),
         })?;
 
         Ok((decoded, index))
@@ -89,11 +146,29 @@ where
     type Output = alloc::vec::Vec<<T as DecodeOwned>::Output>;
 
     fn decode_owned(buff: &[u8]) -> Result<(Self::Output, usize), Self::Error> {
+        Self::decode_owned_limited(buff, &mut DecodeLimits::new())
+    }
+
+    fn decode_owned_limited(
+        buff: &[u8],
+        limits: &mut DecodeLimits,
+    ) -> Result<(Self::Output, usize), Self::Error> {
+        let mut limits = limits.scope()?;
+
         let mut index = 0;
         let mut v = alloc::vec::Vec::new();
 
         while index < buff.len() {
-            let (d, n) = T::decode(&buff[index..])?;
+            // Check limits prior to decoding / allocating each element
+            limits.elements(v.len() + 1, index)?;
+            limits.alloc(core::mem::size_of::<T>(), index)?;
+
+            let (d, n) = T::decode_owned_limited(&buff[index..], &mut limits)?;
+
+            // Zero-length objects can not consume the buffer
+            if n == 0 {
+                return Err(Error::InvalidValue { offset: index }.into());
+            }
 
             v.push(d);
             index += n;
@@ -115,14 +190,30 @@ where
     type Output = heapless::Vec<<T as DecodeOwned>::Output, N>;
 
     fn decode_owned(buff: &[u8]) -> Result<(Self::Output, usize), Self::Error> {
+        Self::decode_owned_limited(buff, &mut DecodeLimits::new())
+    }
+
+    fn decode_owned_limited(
+        buff: &[u8],
+        limits: &mut DecodeLimits,
+    ) -> Result<(Self::Output, usize), Self::Error> {
+        let mut limits = limits.scope()?;
+
         let mut index = 0;
         let mut v = heapless::Vec::new();
 
         while index < buff.len() {
-            let (d, n) = T::decode(&buff[index..])?;
+            limits.elements(v.len() + 1, index)?;
+
+            let (d, n) = T::decode_owned_limited(&buff[index..], &mut limits)?;
+
+            // Zero-length objects can not consume the buffer
+            if n == 0 {
+                return Err(Error::InvalidValue { offset: index }.into());
+            }
 
             if let Err(_e) = v.push(d) {
-                return Err(Error::Length.into());
+                return Err(Error::Overflow { offset: index }.into());
             }
 
             index += n;
@@ -31,11 +31,23 @@ where
         let mut index = 0;
 
         // First, decode prefix
-        let (len, n) = P::decode(&buff)?;
+        let (len, n) = P::decode(buff)?;
         index += n;
 
         // Then, decode the body using this length
-        let (b, n) = T::decode(&buff[index..][..len.as_()])?;
+        let len: usize = len.as_();
+        let body = match buff.get(index..).and_then(|b| b.get(..len)) {
+            Some(b) => b,
+            None => {
+                return Err(Error::Incomplete {
+                    needed: index.saturating_add(len) - buff.len(),
+                    offset: index,
+                }
+                .into())
+            }
+        };
+
+        let (b, n) = T::decode(body)?;
         index += n;
 
         Ok((b, index))
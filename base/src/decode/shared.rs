@@ -0,0 +1,66 @@
+//! Decode helpers for shared [`bytes::Bytes`] buffers
+//!
+
+use core::fmt::Debug;
+
+use bytes::Bytes;
+
+use super::DecodeOwned;
+use crate::Error;
+
+/// Decode trait for objects decoded from a shared [`Bytes`] buffer, allowing
+/// byte fields to be returned as cheap (reference counted) slices of the source
+/// rather than copied.
+///
+/// This is implemented for all [`DecodeOwned`] types and for [`Bytes`], with
+/// implementations for objects containing [`Bytes`] fields generated by
+/// `#[derive(DecodeBytes)]`.
+pub trait DecodeBytes: Sized {
+    /// Error type returned on parse error
+    type Error: From<Error> + Debug;
+
+    /// Decode consumes a [`Bytes`] buffer and returns an object and decoded length.
+    fn decode_bytes(buff: &Bytes) -> Result<(Self, usize), Self::Error>;
+
+    /// Decode an object with an explicit length (ie. from a `#[encdec(length = "..")]`
+    /// field), returning [`Error::TrailingBytes`] where the object is shorter than `len`.
+    fn decode_bytes_len(buff: &Bytes, len: usize) -> Result<Self, Self::Error> {
+        if buff.len() < len {
+            return Err(Error::Incomplete {
+                needed: len - buff.len(),
+                offset: 0,
+            }
+            .into());
+        }
+
+        let (v, n) = Self::decode_bytes(&buff.slice(..len))?;
+        if n < len {
+            return Err(Error::TrailingBytes {
+                remaining: len - n,
+                offset: n,
+            }
+            .into());
+        }
+
+        Ok(v)
+    }
+}
+
+/// Blanket [`DecodeBytes`] impl for [`DecodeOwned`] types
+impl<T: DecodeOwned<Output = T>> DecodeBytes for T {
+    type Error = <T as DecodeOwned>::Error;
+
+    fn decode_bytes(buff: &Bytes) -> Result<(Self, usize), Self::Error> {
+        T::decode_owned(buff)
+    }
+}
+
+/// [`DecodeBytes`] for [`Bytes`], consuming the remainder of the buffer
+/// (as with [`alloc::vec::Vec`]) without copying
+impl DecodeBytes for Bytes {
+    type Error = Error;
+
+    fn decode_bytes(buff: &Bytes) -> Result<(Self, usize), Self::Error> {
+        Ok((buff.clone(), buff.len()))
+    }
+}
@@ -0,0 +1,467 @@
+//! Decoding from non-contiguous input, such as wrapped ring buffers
+
+use core::{fmt::Debug, marker::PhantomData};
+
+use super::{Decode, DecodeLimits, DecodeOwned, Pod};
+use crate::Error;
+
+/// Maximum length of non-contiguous input copied via the stack buffer in [`decode_scratch`]
+pub const SPLIT_SCRATCH: usize = 64;
+
+/// Non-contiguous input, comprising a head slice followed by a tail slice
+/// (for example the two regions of a wrapped ring buffer)
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct SplitBuf<'a> {
+    head: &'a [u8],
+    tail: &'a [u8],
+}
+
+impl<'a> SplitBuf<'a> {
+    /// Create a new [`SplitBuf`] from head and tail slices
+    pub fn new(head: &'a [u8], tail: &'a [u8]) -> Self {
+        match head.is_empty() {
+            true => Self {
+                head: tail,
+                tail: &[],
+            },
+            false => Self { head, tail },
+        }
+    }
+
+    /// Create a [`SplitBuf`] for `len` bytes of a ring buffer starting at `start`,
+    /// wrapping to the start of the buffer where required
+    pub fn from_ring(buff: &'a [u8], start: usize, len: usize) -> Self {
+        let start = start.min(buff.len());
+        let len = len.min(buff.len());
+
+        let head = &buff[start..buff.len().min(start + len)];
+        let tail = &buff[..len - head.len()];
+
+        Self::new(head, tail)
+    }
+
+    /// Fetch the head slice
+    pub fn head(&self) -> &'a [u8] {
+        self.head
+    }
+
+    /// Fetch the tail slice
+    pub fn tail(&self) -> &'a [u8] {
+        self.tail
+    }
+
+    /// Fetch the total length
+    pub fn len(&self) -> usize {
+        self.head.len() + self.tail.len()
+    }
+
+    /// Check whether the buffer is empty
+    pub fn is_empty(&self) -> bool {
+        self.head.is_empty()
+    }
+
+    /// Check whether the buffer is contiguous (ie. has no tail)
+    pub fn is_contiguous(&self) -> bool {
+        self.tail.is_empty()
+    }
+
+    /// Skip `n` bytes from the start of the buffer
+    pub fn advance(&self, n: usize) -> Self {
+        match n < self.head.len() {
+            true => Self::new(&self.head[n..], self.tail),
+            false => {
+                let n = (n - self.head.len()).min(self.tail.len());
+                Self::new(&self.tail[n..], &[])
+            }
+        }
+    }
+
+    /// Copy data from the start of the buffer, returning the number of bytes copied
+    pub fn copy_to(&self, buff: &mut [u8]) -> usize {
+        let a = self.head.len().min(buff.len());
+        buff[..a].copy_from_slice(&self.head[..a]);
+
+        let b = self.tail.len().min(buff.len() - a);
+        buff[a..][..b].copy_from_slice(&self.tail[..b]);
+
+        a + b
+    }
+}
+
+/// Scratch buffer for contiguous views of fields spanning the split in a [`SplitBuf`]
+#[derive(Debug, Default)]
+pub struct Scratch<'a> {
+    buff: &'a mut [u8],
+}
+
+impl<'a> Scratch<'a> {
+    /// Create a new [`Scratch`] using the provided buffer
+    pub fn new(buff: &'a mut [u8]) -> Self {
+        Self { buff }
+    }
+
+    /// Fetch the remaining scratch space
+    pub fn remaining(&self) -> usize {
+        self.buff.len()
+    }
+
+    /// Fetch `len` contiguous bytes from the start of the provided buffer,
+    /// borrowing in place where possible and copying into scratch space
+    /// only where the data spans the split
+    pub fn contiguous(&mut self, buff: SplitBuf<'a>, len: usize) -> Result<&'a [u8], Error> {
+        if buff.len() < len {
+            return Err(Error::Incomplete {
+                needed: len - buff.len(),
+                offset: 0,
+            });
+        }
+
+        if len <= buff.head.len() {
+            return Ok(&buff.head[..len]);
+        }
+
+        if self.buff.len() < len {
+            return Err(Error::BufferTooSmall {
+                needed: len,
+                available: self.buff.len(),
+                offset: 0,
+            });
+        }
+
+        let (data, rest) = core::mem::take(&mut self.buff).split_at_mut(len);
+        self.buff = rest;
+
+        buff.copy_to(data);
+
+        Ok(data)
+    }
+}
+
+/// Decode an object from a [`SplitBuf`] using a slice-based decode function,
+/// for `with` / `dec` overrides of unknown length.
+///
+/// Where the input spans the split the remaining input is copied to contiguous memory,
+/// via a stack buffer where it fits in [`SPLIT_SCRATCH`] bytes and otherwise via unused
+/// [`Scratch`] space, returning [`Error::BufferTooSmall`] where neither can hold the
+/// remaining input rather than decoding from truncated data.
+pub fn decode_scratch<'a, O, E: From<Error>>(
+    buff: SplitBuf<'a>,
+    scratch: &mut Scratch<'a>,
+    f: impl FnOnce(&[u8]) -> Result<(O, usize), E>,
+) -> Result<(O, usize), E> {
+    if buff.is_contiguous() {
+        return f(buff.head);
+    }
+
+    let len = buff.len();
+    let mut s = [0u8; SPLIT_SCRATCH];
+
+    let s = match len <= SPLIT_SCRATCH {
+        true => &mut s[..len],
+        false => match scratch.buff.get_mut(..len) {
+            Some(s) => s,
+            None => {
+                return Err(Error::BufferTooSmall {
+                    needed: len,
+                    available: SPLIT_SCRATCH.max(scratch.remaining()),
+                    offset: 0,
+                }
+                .into())
+            }
+        },
+    };
+
+    buff.copy_to(s);
+
+    f(s)
+}
+
+/// Decode trait for objects that can be decoded from non-contiguous input,
+/// with owned fields decoded across the split and borrowed fields copied
+/// into [`Scratch`] space only where they span the split.
+///
+/// Implementations for derived types may be generated with `#[derive(DecodeSplit)]`.
+pub trait DecodeSplit<'a>: Decode<'a> {
+    /// Decode from a [`SplitBuf`], returning an object and decoded length
+    fn decode_split(
+        buff: SplitBuf<'a>,
+        scratch: &mut Scratch<'a>,
+    ) -> Result<(Self::Output, usize), Self::Error>;
+
+    /// Decode from a [`SplitBuf`] with the provided [`DecodeLimits`], for untrusted input.
+    ///
+    /// Defaults to [`DecodeSplit::decode_split`], see [`Decode::decode_limited`].
+    fn decode_split_limited(
+        buff: SplitBuf<'a>,
+        scratch: &mut Scratch<'a>,
+        limits: &mut DecodeLimits,
+    ) -> Result<(Self::Output, usize), Self::Error> {
+        let _ = limits;
+        Self::decode_split(buff, scratch)
+    }
+}
+
+/// Decode an object from a pair of slices, using the provided scratch buffer
+/// for borrowed fields spanning the split
+pub fn decode_split<'a, T: DecodeSplit<'a>>(
+    head: &'a [u8],
+    tail: &'a [u8],
+    scratch: &'a mut [u8],
+) -> Result<(T::Output, usize), T::Error> {
+    T::decode_split(SplitBuf::new(head, tail), &mut Scratch::new(scratch))
+}
+
+/// Decode an owned object from a pair of slices
+pub fn decode_split_owned<T>(
+    head: &[u8],
+    tail: &[u8],
+) -> Result<(<T as DecodeOwned>::Output, usize), <T as DecodeOwned>::Error>
+where
+    T: DecodeOwned
+        + for<'a> DecodeSplit<
+            'a,
+            Output = <T as DecodeOwned>::Output,
+            Error = <T as DecodeOwned>::Error,
+        >,
+{
+    T::decode_split(SplitBuf::new(head, tail), &mut Scratch::default())
+}
+
+/// Zero-length [`DecodeSplit`] for unit types
+impl<'a> DecodeSplit<'a> for () {
+    fn decode_split(
+        _buff: SplitBuf<'a>,
+        _scratch: &mut Scratch<'a>,
+    ) -> Result<(Self::Output, usize), Self::Error> {
+        Ok(((), 0))
+    }
+}
+
+/// Zero-length [`DecodeSplit`] for [`PhantomData`] markers
+impl<'a, T: ?Sized> DecodeSplit<'a> for PhantomData<T> {
+    fn decode_split(
+        _buff: SplitBuf<'a>,
+        _scratch: &mut Scratch<'a>,
+    ) -> Result<(Self::Output, usize), Self::Error> {
+        Ok((PhantomData, 0))
+    }
+}
+
+/// [`DecodeSplit`] for `[T; N]`s containing [`DecodeSplit`] types
+#[cfg(not(feature = "nightly"))]
+impl<'a, T, const N: usize> DecodeSplit<'a> for [T; N]
+where
+    T: DecodeOwned<Output = T>
+        + DecodeSplit<'a, Output = T, Error = <T as DecodeOwned>::Error>
+        + Debug
+        + Default
+        + Copy,
+{
+    fn decode_split(
+        buff: SplitBuf<'a>,
+        scratch: &mut Scratch<'a>,
+    ) -> Result<(Self::Output, usize), Self::Error> {
+        Self::decode_split_limited(buff, scratch, &mut DecodeLimits::new())
+    }
+
+    fn decode_split_limited(
+        buff: SplitBuf<'a>,
+        scratch: &mut Scratch<'a>,
+        limits: &mut DecodeLimits,
+    ) -> Result<(Self::Output, usize), Self::Error> {
+        let mut data: [T; N] = [T::default(); N];
+
+        let mut offset = 0;
+        for value in data.iter_mut() {
+            let (output, length) = T::decode_split_limited(buff.advance(offset), scratch, limits)?;
+            offset += length;
+            *value = output;
+        }
+
+        Ok((data, offset))
+    }
+}
+
+/// [`DecodeSplit`] for `[T; N]`s containing [`DecodeSplit`] types
+#[cfg(feature = "nightly")]
+impl<'a, T, const N: usize> DecodeSplit<'a> for [T; N]
+where
+    T: DecodeOwned<Output = T>
+        + DecodeSplit<'a, Output = T, Error = <T as DecodeOwned>::Error>
+        + Debug,
+{
+    fn decode_split(
+        buff: SplitBuf<'a>,
+        scratch: &mut Scratch<'a>,
+    ) -> Result<(Self::Output, usize), Self::Error> {
+        Self::decode_split_limited(buff, scratch, &mut DecodeLimits::new())
+    }
+
+    fn decode_split_limited(
+        buff: SplitBuf<'a>,
+        scratch: &mut Scratch<'a>,
+        limits: &mut DecodeLimits,
+    ) -> Result<(Self::Output, usize), Self::Error> {
+        let mut index = 0;
+
+        let decoded = core::array::try_from_fn(|_i| {
+            match T::decode_split_limited(buff.advance(index), scratch, limits) {
+                Ok((o, l)) => {
+                    index += l;
+                    Ok(o)
+                }
+                Err(e) => Err(e),
+            }
+        })?;
+
+        Ok((decoded, index))
+    }
+}
+
+/// Zero-copy [`DecodeSplit`] for references to arrays of [`Pod`] types,
+/// copied into [`Scratch`] space where spanning the split
+impl<'a, T: Pod, const N: usize> DecodeSplit<'a> for &'a [T; N] {
+    fn decode_split(
+        buff: SplitBuf<'a>,
+        scratch: &mut Scratch<'a>,
+    ) -> Result<(Self::Output, usize), Self::Error> {
+        let b = scratch.contiguous(buff, core::mem::size_of::<[T; N]>())?;
+        <&'a [T; N]>::decode(b)
+    }
+}
+
+/// Decode elements across the split until the buffer is consumed,
+/// accounting `alloc` bytes per element against allocation limits
+#[cfg(any(feature = "alloc", feature = "heapless"))]
+fn decode_elements<'a, T>(
+    buff: SplitBuf<'a>,
+    scratch: &mut Scratch<'a>,
+    limits: &mut DecodeLimits,
+    alloc: usize,
+    mut push: impl FnMut(T::Output, usize) -> Result<(), Error>,
+) -> Result<usize, T::Error>
+where
+    T: DecodeSplit<'a>,
+{
+    let mut limits = limits.scope()?;
+
+    let mut index = 0;
+    let mut count = 0;
+
+    while index < buff.len() {
+        limits.elements(count + 1, index)?;
+        limits.alloc(alloc, index)?;
+
+        let (d, n) = T::decode_split_limited(buff.advance(index), scratch, &mut limits)?;
+
+        // Zero-length objects can not consume the buffer
+        if n == 0 {
+            return Err(Error::InvalidValue { offset: index }.into());
+        }
+
+        push(d, index)?;
+        count += 1;
+        index += n;
+    }
+
+    Ok(index)
+}
+
+/// [`DecodeSplit`] for [`alloc::vec::Vec`]s containing [`DecodeSplit`] types
+#[cfg(feature = "alloc")]
+impl<'a, T> DecodeSplit<'a> for alloc::vec::Vec<T>
+where
+    T: DecodeOwned<Output = T>
+        + DecodeSplit<'a, Output = T, Error = <T as DecodeOwned>::Error>
+        + Debug,
+{
+    fn decode_split(
+        buff: SplitBuf<'a>,
+        scratch: &mut Scratch<'a>,
+    ) -> Result<(Self::Output, usize), Self::Error> {
+        Self::decode_split_limited(buff, scratch, &mut DecodeLimits::new())
+    }
+
+    fn decode_split_limited(
+        buff: SplitBuf<'a>,
+        scratch: &mut Scratch<'a>,
+        limits: &mut DecodeLimits,
+    ) -> Result<(Self::Output, usize), Self::Error> {
+        let mut v = alloc::vec::Vec::new();
+
+        let n = decode_elements::<T>(
+            buff,
+            scratch,
+            limits,
+            core::mem::size_of::<T>(),
+            |d, _index| {
+                v.push(d);
+                Ok(())
+            },
+        )?;
+
+        Ok((v, n))
+    }
+}
+
+/// [`DecodeSplit`] for [`heapless::Vec`]s containing [`DecodeSplit`] types
+#[cfg(feature = "heapless")]
+impl<'a, T, const N: usize> DecodeSplit<'a> for heapless::Vec<T, N>
+where
+    T: DecodeOwned<Output = T>
+        + DecodeSplit<'a, Output = T, Error = <T as DecodeOwned>::Error>
+        + Debug,
+{
+    fn decode_split(
+        buff: SplitBuf<'a>,
+        scratch: &mut Scratch<'a>,
+    ) -> Result<(Self::Output, usize), Self::Error> {
+        Self::decode_split_limited(buff, scratch, &mut DecodeLimits::new())
+    }
+
+    fn decode_split_limited(
+        buff: SplitBuf<'a>,
+        scratch: &mut Scratch<'a>,
+        limits: &mut DecodeLimits,
+    ) -> Result<(Self::Output, usize), Self::Error> {
+        let mut v = heapless::Vec::new();
+
+        let n = decode_elements::<T>(buff, scratch, limits, 0, |d, index| {
+            v.push(d).map_err(|_| Error::Overflow { offset: index })
+        })?;
+
+        Ok((v, n))
+    }
+}
+
+/// [`DecodeSplit`] for primitive types, copied into a stack buffer where spanning the split
+macro_rules! impl_split {
+    ($($t:ty),*) => {
+        $(
+            impl<'a> DecodeSplit<'a> for $t {
+                fn decode_split(
+                    buff: SplitBuf<'a>,
+                    _scratch: &mut Scratch<'a>,
+                ) -> Result<(Self::Output, usize), Self::Error> {
+                    const N: usize = core::mem::size_of::<$t>();
+
+                    if buff.head.len() >= N {
+                        return <$t>::decode_owned(buff.head);
+                    }
+
+                    let mut s = [0u8; N];
+                    if buff.copy_to(&mut s) < N {
+                        return Err(Error::Incomplete {
+                            needed: N - buff.len(),
+                            offset: 0,
+                        });
+                    }
+
+                    <$t>::decode_owned(&s)
+                }
+            }
+        )*
+    };
+}
+
+impl_split!(bool, u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
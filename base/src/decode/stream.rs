@@ -0,0 +1,208 @@
+//! Incremental (sans-IO) decoding for partially received data
+
+use core::marker::PhantomData;
+
+use super::DecodeOwned;
+use crate::{DecodeError, Error};
+
+/// Buffer used by [`StreamDecoder`] to accumulate partially received data
+pub trait StreamBuffer {
+    /// Fetch buffered data
+    fn data(&self) -> &[u8];
+
+    /// Fetch the buffer capacity, or `None` where the buffer is unbounded
+    fn capacity(&self) -> Option<usize>;
+
+    /// Append data to the buffer, returning the number of bytes accepted
+    fn push(&mut self, data: &[u8]) -> usize;
+
+    /// Remove `n` bytes from the start of the buffer
+    fn consume(&mut self, n: usize);
+}
+
+/// [`StreamBuffer`] implementation for [`alloc::vec::Vec`]
+#[cfg(feature = "alloc")]
+impl StreamBuffer for alloc::vec::Vec<u8> {
+    fn data(&self) -> &[u8] {
+        self
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        None
+    }
+
+    fn push(&mut self, data: &[u8]) -> usize {
+        self.extend_from_slice(data);
+        data.len()
+    }
+
+    fn consume(&mut self, n: usize) {
+        self.drain(..n.min(self.len()));
+    }
+}
+
+/// [`StreamBuffer`] implementation for fixed capacity [`heapless::Vec`]s
+#[cfg(feature = "heapless")]
+impl<const N: usize> StreamBuffer for heapless::Vec<u8, N> {
+    fn data(&self) -> &[u8] {
+        self
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        Some(N)
+    }
+
+    fn push(&mut self, data: &[u8]) -> usize {
+        let n = data.len().min(N - self.len());
+        // Length is checked above so this can not fail
+        let _ = self.extend_from_slice(&data[..n]);
+        n
+    }
+
+    fn consume(&mut self, n: usize) {
+        let n = n.min(self.len());
+        self.copy_within(n.., 0);
+        self.truncate(self.len() - n);
+    }
+}
+
+/// Status returned by [`StreamDecoder::poll`]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum DecodeStatus<T> {
+    /// Object decoded
+    Complete(T),
+    /// More data required, with the minimum number of additional bytes
+    NeedMore(usize),
+}
+
+/// Incremental (sans-IO) decoder, accumulating chunks of data in a reusable
+/// [`StreamBuffer`] and yielding objects once complete.
+///
+/// Objects must be self-delimiting (ie. fixed size or length prefixed).
+/// Decoding is only re-attempted once the number of bytes reported as
+/// required by the previous attempt has been received, so trickling data
+/// does not result in re-parsing on every chunk.
+///
+/// On a decode error buffered data is discarded, as the stream position is
+/// no longer known.
+///
+/// ```
+/// # #[cfg(feature = "alloc")]
+/// # fn main() {
+/// use encdec_base::decode::{DecodeStatus, StreamDecoder};
+///
+/// let mut d = StreamDecoder::<u32, Vec<u8>>::new();
+///
+/// d.push(&[0x01, 0x02]);
+/// assert_eq!(d.poll(), Ok(DecodeStatus::NeedMore(2)));
+///
+/// d.push(&[0x03, 0x04, 0x05]);
+/// assert_eq!(d.poll(), Ok(DecodeStatus::Complete(0x04030201)));
+/// assert_eq!(d.poll(), Ok(DecodeStatus::NeedMore(3)));
+/// # }
+/// # #[cfg(not(feature = "alloc"))]
+/// # fn main() {}
+/// ```
+#[derive(Debug)]
+pub struct StreamDecoder<T, B> {
+    buff: B,
+    pending: usize,
+    _t: PhantomData<fn() -> T>,
+}
+
+impl<T, B> StreamDecoder<T, B>
+where
+    T: DecodeOwned,
+    T::Error: DecodeError,
+    B: StreamBuffer,
+{
+    /// Create a new [`StreamDecoder`] with a default buffer
+    pub fn new() -> Self
+    where
+        B: Default,
+    {
+        Self::with_buffer(B::default())
+    }
+
+    /// Create a new [`StreamDecoder`] using the provided buffer
+    pub fn with_buffer(buff: B) -> Self {
+        Self {
+            buff,
+            pending: 0,
+            _t: PhantomData,
+        }
+    }
+
+    /// Push a chunk of received data, returning the number of bytes accepted
+    /// (which may be less than provided where the buffer is full)
+    pub fn push(&mut self, data: &[u8]) -> usize {
+        let n = self.buff.push(data);
+        self.pending = self.pending.saturating_sub(n);
+        n
+    }
+
+    /// Attempt to decode the next object from buffered data
+    pub fn poll(&mut self) -> Result<DecodeStatus<T::Output>, T::Error> {
+        // Skip decoding until the previously reported data is available
+        if self.pending > 0 {
+            return Ok(DecodeStatus::NeedMore(self.pending));
+        }
+
+        let e = match T::decode_owned(self.buff.data()) {
+            Ok((v, n)) => {
+                self.buff.consume(n);
+                return Ok(DecodeStatus::Complete(v));
+            }
+            Err(e) => e,
+        };
+
+        let needed = match e.incomplete() {
+            Some(n) => n.max(1),
+            None => {
+                self.clear();
+                return Err(e);
+            }
+        };
+
+        // Check the object can fit in the buffer
+        let len = self.buff.data().len();
+        match self.buff.capacity() {
+            Some(c) if len.saturating_add(needed) > c => {
+                self.clear();
+                Err(Error::BufferTooSmall {
+                    needed: len.saturating_add(needed),
+                    available: c,
+                    offset: 0,
+                }
+                .into())
+            }
+            _ => {
+                self.pending = needed;
+                Ok(DecodeStatus::NeedMore(needed))
+            }
+        }
+    }
+
+    /// Fetch buffered data not yet decoded
+    pub fn buffered(&self) -> &[u8] {
+        self.buff.data()
+    }
+
+    /// Discard buffered data, resetting the decoder
+    pub fn clear(&mut self) {
+        let n = self.buff.data().len();
+        self.buff.consume(n);
+        self.pending = 0;
+    }
+}
+
+impl<T, B> Default for StreamDecoder<T, B>
+where
+    T: DecodeOwned,
+    T::Error: DecodeError,
+    B: StreamBuffer + Default,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -3,6 +3,7 @@
 
 use core::fmt::Debug;
 
+use super::DecodeLimits;
 use crate::Error;
 
 /// Decode helper trait for for fields with external length tags
@@ -16,20 +17,15 @@ pub trait DecodedTagged<'a> {
 
     /// Decode consumes a slice and explicit length and returns an object
     fn decode_len(buff: &'a [u8], len: usize) -> Result<Self::Output, Self::Error>;
-}
-
-/// [`DecodedTagged`] impl for byte arrays
-/// (requires `#[encdec(length=...)]` length delimiter)
-impl<'a> DecodedTagged<'a> for &[u8] {
-    type Output = &'a [u8];
-    type Error = Error;
-
-    fn decode_len(buff: &'a [u8], len: usize) -> Result<Self::Output, Self::Error> {
-        if buff.len() < len {
-            return Err(Error::Length);
-        }
 
-        Ok(&buff[..len])
+    /// Decode with an explicit length and the provided [`DecodeLimits`]
+    fn decode_len_limited(
+        buff: &'a [u8],
+        len: usize,
+        limits: &mut DecodeLimits,
+    ) -> Result<Self::Output, Self::Error> {
+        let _ = limits;
+        Self::decode_len(buff, len)
     }
 }
 
@@ -41,7 +37,10 @@ impl<'a> DecodedTagged<'a> for &str {
 
     fn decode_len(buff: &'a [u8], len: usize) -> Result<Self::Output, Self::Error> {
         if buff.len() < len {
-            return Err(Error::Length);
+            return Err(Error::Incomplete {
+                needed: len - buff.len(),
+                offset: 0,
+            });
         }
 
         match core::str::from_utf8(&buff[..len]) {
@@ -49,4 +48,13 @@ impl<'a> DecodedTagged<'a> for &str {
             Err(_e) => Err(Error::Utf8),
         }
     }
+
+    fn decode_len_limited(
+        buff: &'a [u8],
+        len: usize,
+        limits: &mut DecodeLimits,
+    ) -> Result<Self::Output, Self::Error> {
+        limits.str_len(len, 0)?;
+        Self::decode_len(buff, len)
+    }
 }
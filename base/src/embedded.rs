@@ -0,0 +1,157 @@
+//! [`embedded_io`] adapters for encoding to writers and decoding from readers
+//! in `no_std` environments, using caller-provided scratch buffers.
+//!
+//! Async variants using [`embedded_io_async`] are available with the
+//! `embedded-io-async` feature.
+
+use core::fmt;
+
+use embedded_io::ReadExactError;
+
+use crate::{decode::DecodeOwned, encode::Encode, DecodeError, Error};
+
+/// Error type for [`embedded_io`] adapters, wrapping IO and encode / decode errors
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum IoError<E, I> {
+    /// Underlying IO error
+    Io(I),
+    /// Reader ended before an object was complete
+    UnexpectedEof,
+    /// Encode or decode error
+    Codec(E),
+}
+
+impl<E, I> From<ReadExactError<I>> for IoError<E, I> {
+    fn from(e: ReadExactError<I>) -> Self {
+        match e {
+            ReadExactError::UnexpectedEof => Self::UnexpectedEof,
+            ReadExactError::Other(e) => Self::Io(e),
+        }
+    }
+}
+
+impl<E: fmt::Display, I: fmt::Debug> fmt::Display for IoError<E, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IoError::Io(e) => write!(f, "io error: {e:?}"),
+            IoError::UnexpectedEof => write!(f, "unexpected end of input"),
+            IoError::Codec(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+/// Encode an object to an [`embedded_io::Write`]r via the provided scratch buffer
+pub fn encode_to_writer<T, W>(
+    v: &T,
+    w: &mut W,
+    scratch: &mut [u8],
+) -> Result<usize, IoError<T::Error, W::Error>>
+where
+    T: Encode,
+    W: embedded_io::Write,
+{
+    let n = v.encode(scratch).map_err(IoError::Codec)?;
+    w.write_all(&scratch[..n]).map_err(IoError::Io)?;
+
+    Ok(n)
+}
+
+/// Decode an owned object from an [`embedded_io::Read`]er via the provided scratch
+/// buffer, reading only the bytes required for the object.
+///
+/// Objects must be self-delimiting (ie. fixed size or length prefixed), returning
+/// [`Error::BufferTooSmall`] where the object exceeds the scratch buffer.
+pub fn decode_from_reader<T, R>(
+    r: &mut R,
+    scratch: &mut [u8],
+) -> Result<T::Output, IoError<T::Error, R::Error>>
+where
+    T: DecodeOwned,
+    T::Error: DecodeError,
+    R: embedded_io::Read,
+{
+    let mut len = 0;
+
+    loop {
+        let end = match next_read::<T, _>(scratch, len)? {
+            Ok(v) => return Ok(v),
+            Err(end) => end,
+        };
+
+        r.read_exact(&mut scratch[len..end])?;
+        len = end;
+    }
+}
+
+/// Async variant of [`encode_to_writer`] for [`embedded_io_async::Write`]rs
+#[cfg(feature = "embedded-io-async")]
+pub async fn encode_to_writer_async<T, W>(
+    v: &T,
+    w: &mut W,
+    scratch: &mut [u8],
+) -> Result<usize, IoError<T::Error, W::Error>>
+where
+    T: Encode,
+    W: embedded_io_async::Write,
+{
+    let n = v.encode(scratch).map_err(IoError::Codec)?;
+    w.write_all(&scratch[..n]).await.map_err(IoError::Io)?;
+
+    Ok(n)
+}
+
+/// Async variant of [`decode_from_reader`] for [`embedded_io_async::Read`]ers
+#[cfg(feature = "embedded-io-async")]
+pub async fn decode_from_reader_async<T, R>(
+    r: &mut R,
+    scratch: &mut [u8],
+) -> Result<T::Output, IoError<T::Error, R::Error>>
+where
+    T: DecodeOwned,
+    T::Error: DecodeError,
+    R: embedded_io_async::Read,
+{
+    let mut len = 0;
+
+    loop {
+        let end = match next_read::<T, _>(scratch, len)? {
+            Ok(v) => return Ok(v),
+            Err(end) => end,
+        };
+
+        r.read_exact(&mut scratch[len..end]).await?;
+        len = end;
+    }
+}
+
+/// Attempt to decode the first `len` bytes of the scratch buffer, returning the
+/// decoded object or the end of the next read where more data is required
+fn next_read<T, I>(
+    scratch: &[u8],
+    len: usize,
+) -> Result<Result<T::Output, usize>, IoError<T::Error, I>>
+where
+    T: DecodeOwned,
+    T::Error: DecodeError,
+{
+    let e = match T::decode_owned(&scratch[..len]) {
+        Ok((v, _n)) => return Ok(Ok(v)),
+        Err(e) => e,
+    };
+
+    let end = match e.incomplete() {
+        Some(n) => len.saturating_add(n.max(1)),
+        None => return Err(IoError::Codec(e)),
+    };
+
+    if end > scratch.len() {
+        let e = Error::BufferTooSmall {
+            needed: end,
+            available: scratch.len(),
+            offset: 0,
+        };
+        return Err(IoError::Codec(e.into()));
+    }
+
+    Ok(Err(end))
+}
@@ -0,0 +1,395 @@
+//! Resumable chunked encoding into successive (small) output buffers
+
+use core::marker::PhantomData;
+
+use super::{Encode, Sink};
+use crate::Error;
+
+/// Maximum encoded length of objects split via the stack scratch buffer in [`encode_window`]
+pub const CHUNK_SCRATCH: usize = 64;
+
+/// Encode trait for objects that can be encoded in chunks, writing a window
+/// of the encoded object starting at an arbitrary offset.
+///
+/// This allows large objects to be streamed out through small buffers without
+/// a full-size intermediate buffer, see [`ChunkedEncoder`].
+/// Implementations for derived types may be generated with `#[derive(EncodeChunked)]`.
+pub trait EncodeChunked: Encode {
+    /// Encode the bytes of the encoded object from `offset` into `buff`,
+    /// returning the number of bytes written (limited by the buffer length)
+    fn encode_range(&self, offset: usize, buff: &mut [u8]) -> Result<usize, Self::Error>;
+
+    /// Encode the bytes of a slice of objects from `offset` into `buff`, used by
+    /// [`EncodeChunked`] implementations for slices, arrays and vectors.
+    ///
+    /// The default walks each element in turn, fixed size types skip directly to
+    /// the element containing `offset` and bytes are copied without per-element encoding.
+    fn encode_slice_range(s: &[Self], offset: usize, buff: &mut [u8]) -> Result<usize, Self::Error>
+    where
+        Self: Sized,
+    {
+        let mut w = ChunkWindow::new(offset, buff);
+
+        for v in s.iter() {
+            if w.is_full() {
+                break;
+            }
+            w.field(v.encode_len()?, |o, b| v.encode_range(o, b))?;
+        }
+
+        Ok(w.finish())
+    }
+}
+
+/// Encode a window of a small object via a scratch buffer, for use in
+/// [`EncodeChunked`] implementations of leaf types.
+///
+/// Objects up to [`CHUNK_SCRATCH`] bytes are staged on the stack, larger objects
+/// are staged on the heap where the `alloc` feature is enabled and otherwise
+/// return [`Error::BufferTooSmall`]. As the whole object is encoded for each window,
+/// large objects are better streamed via [`encode_window_to`].
+pub fn encode_window<E: From<Error>>(
+    len: usize,
+    offset: usize,
+    buff: &mut [u8],
+    f: impl FnOnce(&mut [u8]) -> Result<usize, E>,
+) -> Result<usize, E> {
+    #[cfg(feature = "alloc")]
+    if len > CHUNK_SCRATCH {
+        let mut s = alloc::vec![0u8; len];
+        let n = f(&mut s)?.min(len);
+        return Ok(copy_window(&s[..n], offset, buff));
+    }
+
+    let mut scratch = [0u8; CHUNK_SCRATCH];
+    let s = match scratch.get_mut(..len) {
+        Some(s) => s,
+        None => {
+            return Err(Error::BufferTooSmall {
+                needed: len,
+                available: CHUNK_SCRATCH,
+                offset: 0,
+            }
+            .into())
+        }
+    };
+
+    let n = f(s)?;
+
+    Ok(copy_window(&s[..n.min(len)], offset, buff))
+}
+
+/// Encode a window of an object via a sink-based encoder, copying only the bytes
+/// within the window so objects of any length can be encoded without a scratch buffer.
+pub fn encode_window_to<E>(
+    offset: usize,
+    buff: &mut [u8],
+    f: impl FnOnce(&mut WindowSink) -> Result<usize, E>,
+) -> Result<usize, E> {
+    let mut s = WindowSink {
+        buff,
+        offset,
+        index: 0,
+        written: 0,
+    };
+
+    f(&mut s)?;
+
+    Ok(s.written)
+}
+
+/// [`Sink`] copying the window of written data from an offset into a buffer,
+/// see [`encode_window_to`]
+#[derive(Debug)]
+pub struct WindowSink<'a> {
+    buff: &'a mut [u8],
+    offset: usize,
+    index: usize,
+    written: usize,
+}
+
+impl Sink for WindowSink<'_> {
+    fn write_bytes(&mut self, data: &[u8]) -> Result<(), Error> {
+        // Skip data prior to the window
+        let start = self.index;
+        self.index += data.len();
+        let skip = self.offset.saturating_sub(start);
+
+        if let Some(data) = data.get(skip..) {
+            self.written += copy_window(data, 0, &mut self.buff[self.written..]);
+        }
+
+        Ok(())
+    }
+}
+
+/// Copy the window of `src` from `offset` into `buff`, returning the number of bytes copied
+fn copy_window(src: &[u8], offset: usize, buff: &mut [u8]) -> usize {
+    let src = src.get(offset..).unwrap_or_default();
+    let n = src.len().min(buff.len());
+    buff[..n].copy_from_slice(&src[..n]);
+    n
+}
+
+/// Helper for [`EncodeChunked`] implementations over sequences of fields or elements,
+/// tracking the position of each field within the encoded object
+#[derive(Debug)]
+pub struct ChunkWindow<'a> {
+    buff: &'a mut [u8],
+    offset: usize,
+    index: usize,
+    written: usize,
+}
+
+impl<'a> ChunkWindow<'a> {
+    /// Create a new [`ChunkWindow`] writing the encoded object from `offset` into `buff`
+    pub fn new(offset: usize, buff: &'a mut [u8]) -> Self {
+        Self {
+            buff,
+            offset,
+            index: 0,
+            written: 0,
+        }
+    }
+
+    /// Fetch the offset of the next field within the encoded object
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Skip `len` bytes of fields known to precede the window without encoding them
+    pub fn skip(&mut self, len: usize) {
+        self.index = self.index.saturating_add(len);
+    }
+
+    /// Check whether the window is full
+    pub fn is_full(&self) -> bool {
+        self.written >= self.buff.len()
+    }
+
+    /// Write the portion of a field of encoded length `len` within the window,
+    /// calling `f` with the offset within the field and the remaining buffer
+    pub fn field<E>(
+        &mut self,
+        len: usize,
+        f: impl FnOnce(usize, &mut [u8]) -> Result<usize, E>,
+    ) -> Result<(), E> {
+        let pos = self.offset + self.written;
+        let end = self.index.saturating_add(len);
+
+        if pos >= self.index && pos < end && !self.is_full() {
+            let b = &mut self.buff[self.written..];
+            let n = f(pos - self.index, b)?;
+            self.written += n.min(b.len());
+        }
+
+        self.index = end;
+
+        Ok(())
+    }
+
+    /// Fetch the number of bytes written
+    pub fn finish(self) -> usize {
+        self.written
+    }
+}
+
+/// Resumable encoder writing an [`EncodeChunked`] object into successive buffers
+///
+/// ```
+/// use encdec_base::encode::ChunkedEncoder;
+///
+/// let v = [0x0102u16, 0x0304, 0x0506];
+/// let mut e = ChunkedEncoder::new(&v).unwrap();
+///
+/// let mut buff = [0u8; 4];
+/// assert_eq!(e.next_chunk(&mut buff), Ok(4));
+/// assert_eq!(buff, [0x02, 0x01, 0x04, 0x03]);
+/// assert_eq!(e.next_chunk(&mut buff), Ok(2));
+/// assert_eq!(&buff[..2], &[0x06, 0x05]);
+/// assert!(e.is_complete());
+/// ```
+#[derive(Debug)]
+pub struct ChunkedEncoder<'a, T> {
+    value: &'a T,
+    offset: usize,
+    len: usize,
+}
+
+impl<'a, T: EncodeChunked> ChunkedEncoder<'a, T> {
+    /// Create a new [`ChunkedEncoder`] for the provided object
+    pub fn new(value: &'a T) -> Result<Self, T::Error> {
+        Ok(Self {
+            value,
+            offset: 0,
+            len: value.encode_len()?,
+        })
+    }
+
+    /// Encode the next chunk into the provided buffer, returning the number
+    /// of bytes written (`0` once encoding is complete)
+    pub fn next_chunk(&mut self, buff: &mut [u8]) -> Result<usize, T::Error> {
+        if self.is_complete() || buff.is_empty() {
+            return Ok(0);
+        }
+
+        let n = self.value.encode_range(self.offset, buff)?;
+
+        // Guard against implementations writing less than their encoded length
+        if n == 0 {
+            return Err(Error::InvalidValue {
+                offset: self.offset,
+            }
+            .into());
+        }
+
+        self.offset += n;
+
+        Ok(n)
+    }
+
+    /// Fetch the number of bytes encoded so far
+    pub fn position(&self) -> usize {
+        self.offset
+    }
+
+    /// Fetch the number of bytes remaining to be encoded
+    pub fn remaining(&self) -> usize {
+        self.len.saturating_sub(self.offset)
+    }
+
+    /// Check whether encoding is complete
+    pub fn is_complete(&self) -> bool {
+        self.offset >= self.len
+    }
+}
+
+/// [`EncodeChunked`] for references to chunk-encodable types
+impl<T: EncodeChunked> EncodeChunked for &T {
+    fn encode_range(&self, offset: usize, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        <T as EncodeChunked>::encode_range(self, offset, buff)
+    }
+}
+
+/// [`EncodeChunked`] for slices of chunk-encodable types
+impl<T> EncodeChunked for &[T]
+where
+    T: EncodeChunked,
+    <T as Encode>::Error: From<Error>,
+{
+    fn encode_range(&self, offset: usize, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        T::encode_slice_range(self, offset, buff)
+    }
+}
+
+/// [`EncodeChunked`] for arrays of chunk-encodable types
+impl<T, const N: usize> EncodeChunked for [T; N]
+where
+    T: EncodeChunked,
+    <T as Encode>::Error: From<Error>,
+{
+    fn encode_range(&self, offset: usize, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        self.as_slice().encode_range(offset, buff)
+    }
+}
+
+/// [`EncodeChunked`] for [`alloc::vec::Vec`]s of chunk-encodable types
+#[cfg(feature = "alloc")]
+impl<T> EncodeChunked for alloc::vec::Vec<T>
+where
+    T: EncodeChunked,
+    <T as Encode>::Error: From<Error>,
+{
+    fn encode_range(&self, offset: usize, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        self.as_slice().encode_range(offset, buff)
+    }
+}
+
+/// [`EncodeChunked`] for [`heapless::Vec`]s of chunk-encodable types
+#[cfg(feature = "heapless")]
+impl<T, const N: usize> EncodeChunked for heapless::Vec<T, N>
+where
+    T: EncodeChunked,
+    <T as Encode>::Error: From<Error>,
+{
+    fn encode_range(&self, offset: usize, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        self.as_slice().encode_range(offset, buff)
+    }
+}
+
+/// [`EncodeChunked`] for string slices, copying the requested window
+impl EncodeChunked for &str {
+    fn encode_range(&self, offset: usize, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        Ok(copy_window(self.as_bytes(), offset, buff))
+    }
+}
+
+/// [`EncodeChunked`] for shared [`bytes::Bytes`] buffers, copying the requested window
+#[cfg(feature = "bytes")]
+impl EncodeChunked for bytes::Bytes {
+    fn encode_range(&self, offset: usize, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        Ok(copy_window(self, offset, buff))
+    }
+}
+
+/// Zero-length [`EncodeChunked`] for unit types
+impl EncodeChunked for () {
+    fn encode_range(&self, _offset: usize, _buff: &mut [u8]) -> Result<usize, Self::Error> {
+        Ok(0)
+    }
+}
+
+/// Zero-length [`EncodeChunked`] for [`PhantomData`] markers
+impl<T: ?Sized> EncodeChunked for PhantomData<T> {
+    fn encode_range(&self, _offset: usize, _buff: &mut [u8]) -> Result<usize, Self::Error> {
+        Ok(0)
+    }
+}
+
+/// [`EncodeChunked`] for bytes, with slices copied directly
+impl EncodeChunked for u8 {
+    fn encode_range(&self, offset: usize, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        Ok(copy_window(&[*self], offset, buff))
+    }
+
+    fn encode_slice_range(
+        s: &[Self],
+        offset: usize,
+        buff: &mut [u8],
+    ) -> Result<usize, Self::Error> {
+        Ok(copy_window(s, offset, buff))
+    }
+}
+
+/// [`EncodeChunked`] for primitive types, via [`encode_window`], with slices
+/// skipping directly to the element containing the window offset
+macro_rules! impl_chunked {
+    ($($t:ty),*) => {
+        $(
+            impl EncodeChunked for $t {
+                fn encode_range(&self, offset: usize, buff: &mut [u8]) -> Result<usize, Self::Error> {
+                    encode_window(core::mem::size_of::<$t>(), offset, buff, |b| self.encode(b))
+                }
+
+                fn encode_slice_range(s: &[Self], offset: usize, buff: &mut [u8]) -> Result<usize, Self::Error> {
+                    const N: usize = core::mem::size_of::<$t>();
+
+                    let skip = (offset / N).min(s.len());
+                    let mut w = ChunkWindow::new(offset, buff);
+                    w.skip(skip * N);
+
+                    for v in &s[skip..] {
+                        if w.is_full() {
+                            break;
+                        }
+                        w.field(N, |o, b| v.encode_range(o, b))?;
+                    }
+
+                    Ok(w.finish())
+                }
+            }
+        )*
+    };
+}
+
+impl_chunked!(bool, i8, u16, i16, u32, i32, u64, i64, f32, f64);
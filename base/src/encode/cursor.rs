@@ -0,0 +1,93 @@
+//! Cursor for writing sequences of objects into an output buffer
+
+use super::Encode;
+use crate::Error;
+
+/// Cursor over an output buffer, tracking the write position for
+/// hand-written (and derived) [`Encode`] implementations.
+///
+/// Errors returned by nested objects are relative to the cursor
+/// [`position`][EncodeCursor::position] at the time of the call.
+/// Writes never index out of bounds, where an object reports a length exceeding
+/// the buffer subsequent writes see an empty buffer.
+///
+/// ```
+/// use encdec_base::encode::EncodeCursor;
+///
+/// let mut buff = [0u8; 8];
+/// let mut c = EncodeCursor::new(&mut buff);
+///
+/// c.write(&0x10u8).unwrap();
+/// c.skip(1).unwrap();
+/// c.write(&0xabcdu16).unwrap();
+///
+/// assert_eq!(c.position(), 4);
+/// assert_eq!(c.remaining(), 4);
+/// assert_eq!(c.written(), &[0x10, 0x00, 0xcd, 0xab]);
+/// ```
+#[derive(Debug)]
+pub struct EncodeCursor<'a> {
+    buff: &'a mut [u8],
+    index: usize,
+}
+
+impl<'a> EncodeCursor<'a> {
+    /// Create a new [`EncodeCursor`] writing from the start of the provided buffer
+    pub fn new(buff: &'a mut [u8]) -> Self {
+        Self { buff, index: 0 }
+    }
+
+    /// Encode an object at the current position, advancing past the encoded object
+    pub fn write<T: Encode + ?Sized>(&mut self, v: &T) -> Result<usize, T::Error> {
+        self.write_with(|b| v.encode(b))
+    }
+
+    /// Encode a nested object at the current position via [`Encode::encode_nested`],
+    /// for enclosing objects that have already checked their encoded length
+    pub fn write_nested<T: Encode + ?Sized>(&mut self, v: &T) -> Result<usize, T::Error> {
+        let n = v.encode_nested(self.buff.get_mut(self.index..).unwrap_or_default())?;
+        self.index = self.index.saturating_add(n);
+        Ok(n)
+    }
+
+    /// Write at the current position using the provided encode function,
+    /// advancing by the returned length
+    pub fn write_with<E>(
+        &mut self,
+        f: impl FnOnce(&mut [u8]) -> Result<usize, E>,
+    ) -> Result<usize, E> {
+        let n = f(self.buff.get_mut(self.index..).unwrap_or_default())?;
+        self.index = self.index.saturating_add(n);
+        Ok(n)
+    }
+
+    /// Skip `n` bytes, leaving the buffer contents unchanged
+    pub fn skip(&mut self, n: usize) -> Result<(), Error> {
+        if n > self.remaining() {
+            return Err(Error::BufferTooSmall {
+                needed: n,
+                available: self.remaining(),
+                offset: self.index,
+            });
+        }
+
+        self.index += n;
+
+        Ok(())
+    }
+
+    /// Fetch the current write position (and number of bytes written)
+    pub fn position(&self) -> usize {
+        self.index
+    }
+
+    /// Fetch the number of bytes remaining in the buffer
+    pub fn remaining(&self) -> usize {
+        self.buff.len().saturating_sub(self.index)
+    }
+
+    /// Fetch the data written so far
+    pub fn written(&self) -> &[u8] {
+        &self.buff[..self.index.min(self.buff.len())]
+    }
+}
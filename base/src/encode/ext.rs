@@ -1,4 +1,7 @@
+use core::fmt;
+
 use super::Encode;
+use crate::Error;
 
 /// Extensions to [`Encode`] trait for encodable types
 pub trait EncodeExt<'a>: Encode + Sized + 'a {
@@ -9,12 +12,51 @@ pub trait EncodeExt<'a>: Encode + Sized + 'a {
     ) -> Result<usize, Self::Error> {
         let mut index = 0;
         for i in items {
-            index += i.encode(&mut buff[index..])?;
+            index += i.encode(buff.get_mut(index..).unwrap_or_default())?;
         }
         Ok(index)
     }
 
-    /// Helper to encode to a fixed size buffer
+    /// Encode with an up-front length check, reporting the extent of any
+    /// partially written output on failure.
+    ///
+    /// On error, bytes in `buff` beyond [`PartialEncode::max_written`] are untouched.
+    /// This is an upper bound as encoders do not report progress on failure.
+    fn try_encode(&self, buff: &mut [u8]) -> Result<usize, PartialEncode<Self::Error>> {
+        let n = match self.encode_len() {
+            Ok(n) => n,
+            Err(error) => {
+                return Err(PartialEncode {
+                    error,
+                    max_written: 0,
+                })
+            }
+        };
+
+        if buff.len() < n {
+            let error = Error::BufferTooSmall {
+                needed: n,
+                available: buff.len(),
+                offset: 0,
+            };
+            return Err(PartialEncode {
+                error: error.into(),
+                max_written: 0,
+            });
+        }
+
+        // Restrict encoding to the checked length
+        match self.encode_nested(&mut buff[..n]) {
+            Ok(n) => Ok(n),
+            Err(error) => Err(PartialEncode {
+                error,
+                max_written: n,
+            }),
+        }
+    }
+
+    /// Helper to encode to a fixed size buffer, for types implementing
+    /// [`MaxEncodedLen`][super::MaxEncodedLen] `N` may be set to `T::MAX_ENCODED_LEN`
     fn encode_buff<const N: usize>(&self) -> Result<([u8; N], usize), Self::Error> {
         let mut b = [0u8; N];
         let n = self.encode(&mut b)?;
@@ -26,10 +68,232 @@ pub trait EncodeExt<'a>: Encode + Sized + 'a {
     fn encode_vec(&self) -> Result<(alloc::vec::Vec<u8>, usize), Self::Error> {
         let n = self.encode_len()?;
         let mut b = alloc::vec![0u8; n];
-        let n = self.encode(&mut b)?;
+        let n = self.encode_nested(&mut b)?;
         Ok((b, n))
     }
+
+    /// Helper to encode to a fixed capacity [`heapless::Vec`], uses `encode_len`
+    /// to check the object fits
+    #[cfg(feature = "heapless")]
+    fn encode_heapless<const N: usize>(&self) -> Result<heapless::Vec<u8, N>, Self::Error> {
+        let n = check_capacity(self.encode_len()?, N)?;
+
+        let mut b = heapless::Vec::new();
+        // Length is checked above so this can not fail
+        let _ = b.resize_default(n);
+
+        let n = self.encode_nested(&mut b)?;
+        b.truncate(n);
+
+        Ok(b)
+    }
+
+    /// Helper to encode to a fixed capacity [`arrayvec::ArrayVec`], uses `encode_len`
+    /// to check the object fits
+    #[cfg(feature = "arrayvec")]
+    fn encode_arrayvec<const N: usize>(&self) -> Result<arrayvec::ArrayVec<u8, N>, Self::Error> {
+        let n = check_capacity(self.encode_len()?, N)?;
+
+        let mut b = arrayvec::ArrayVec::new();
+        // Avoids `core::iter::repeat_n` (Rust 1.82) to retain support for older toolchains
+        #[allow(clippy::manual_repeat_n)]
+        b.extend(core::iter::repeat(0).take(n));
+
+        let n = self.encode_nested(&mut b)?;
+        b.truncate(n);
+
+        Ok(b)
+    }
+
+    /// Helper to encode to the end of a vector, retaining existing data.
+    ///
+    /// On error the vector is restored to its original length.
+    #[cfg(feature = "alloc")]
+    fn encode_append(&self, v: &mut alloc::vec::Vec<u8>) -> Result<usize, Self::Error> {
+        let offset = v.len();
+        let n = self.encode_len()?;
+        v.resize(offset + n, 0);
+
+        match self.encode_nested(&mut v[offset..]) {
+            Ok(n) => {
+                v.truncate(offset + n);
+                Ok(n)
+            }
+            Err(e) => {
+                v.truncate(offset);
+                Err(e)
+            }
+        }
+    }
+
+    /// Helper to encode into any [`Extend`]able container, encoding small objects
+    /// on the stack (or via an intermediate allocation with `alloc`).
+    ///
+    /// The container is only extended once encoding has succeeded.
+    fn encode_into(&self, out: &mut impl Extend<u8>) -> Result<usize, Self::Error> {
+        encode_staged(self, |b| {
+            out.extend(b.iter().copied());
+            b.len()
+        })
+    }
+
+    /// Helper to encode to a [`std::io::Write`]r, encoding small objects on the
+    /// stack to avoid intermediate allocations
+    #[cfg(feature = "std")]
+    fn encode_to_writer(
+        &self,
+        w: &mut impl std::io::Write,
+    ) -> Result<usize, crate::io::IoError<Self::Error>> {
+        use crate::io::IoError;
+
+        // Encode errors are returned prior to writing, write errors after
+        let r = encode_staged(self, |b| w.write_all(b).map(|_| b.len()));
+        let n = r.map_err(IoError::Codec)??;
+
+        Ok(n)
+    }
+
+    /// Helper to encode into a [`bytes::BufMut`] (ie. [`bytes::BytesMut`], `Vec<u8>`,
+    /// or limited / chained buffers), following any existing data.
+    ///
+    /// Objects are encoded in place where the next writable chunk fits `encode_len`
+    /// bytes (growable buffers reserve space when full), otherwise they are staged and
+    /// copied so writes may span chunks. Reserve `encode_len` bytes in advance to
+    /// ensure objects are written in place.
+    ///
+    /// On error the buffer is left unchanged.
+    #[cfg(feature = "bytes")]
+    fn encode_buf<B: bytes::BufMut + ?Sized>(&self, buf: &mut B) -> Result<usize, Self::Error> {
+        let n = self.encode_len()?;
+
+        let available = buf.remaining_mut();
+        if available < n {
+            return Err(Error::BufferTooSmall {
+                needed: n,
+                available,
+                offset: 0,
+            }
+            .into());
+        }
+
+        let chunk = buf.chunk_mut();
+        if chunk.len() < n {
+            return encode_staged(self, |b| {
+                buf.put_slice(b);
+                b.len()
+            });
+        }
+
+        // Safety: the chunk holds at least `n` writable bytes, which are
+        // initialised prior to creating the slice
+        let b = unsafe {
+            core::ptr::write_bytes(chunk.as_mut_ptr(), 0, n);
+            core::slice::from_raw_parts_mut(chunk.as_mut_ptr(), n)
+        };
+
+        let n = self.encode_nested(b)?.min(n);
+
+        // Safety: the first `n` bytes of the chunk have been initialised above
+        unsafe { buf.advance_mut(n) };
+
+        Ok(n)
+    }
 }
 
 /// Blanket implementation for all [`Encode`] types
 impl<'a, T: Encode + 'a> EncodeExt<'a> for T {}
+
+/// Size of stack buffers used for staging encoded objects
+const STAGING_STACK: usize = 256;
+
+/// Encode an object into a staging buffer, calling `f` with the encoded data.
+///
+/// Objects up to [`STAGING_STACK`] bytes are encoded on the stack, with larger
+/// objects allocated where `alloc` is enabled.
+fn encode_staged<T: Encode + ?Sized, R>(v: &T, f: impl FnOnce(&[u8]) -> R) -> Result<R, T::Error> {
+    let n = v.encode_len()?;
+
+    let mut stack = [0u8; STAGING_STACK];
+    #[cfg(feature = "alloc")]
+    let mut heap;
+    let b = match n <= stack.len() {
+        true => &mut stack[..n],
+        #[cfg(feature = "alloc")]
+        false => {
+            heap = alloc::vec![0u8; n];
+            &mut heap[..]
+        }
+        #[cfg(not(feature = "alloc"))]
+        false => {
+            return Err(Error::BufferTooSmall {
+                needed: n,
+                available: STAGING_STACK,
+                offset: 0,
+            }
+            .into())
+        }
+    };
+
+    let n = v.encode_nested(b)?;
+
+    Ok(f(&b[..n]))
+}
+
+/// Check an encoded length fits in a fixed capacity container
+#[cfg(any(feature = "heapless", feature = "arrayvec"))]
+fn check_capacity(n: usize, capacity: usize) -> Result<usize, Error> {
+    match n <= capacity {
+        true => Ok(n),
+        false => Err(Error::BufferTooSmall {
+            needed: n,
+            available: capacity,
+            offset: 0,
+        }),
+    }
+}
+
+/// Error returned by [`EncodeExt::try_encode`], recording the encode error
+/// and an upper bound on the extent of written output
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct PartialEncode<E> {
+    error: E,
+    max_written: usize,
+}
+
+impl<E> PartialEncode<E> {
+    /// Fetch the underlying encode error
+    pub fn error(&self) -> &E {
+        &self.error
+    }
+
+    /// Fetch an upper bound on the number of bytes written from the start of the buffer,
+    /// bytes beyond this are untouched.
+    ///
+    /// This is the expected encoded length where encoding failed part way through,
+    /// as encoders do not report the position of failures.
+    pub fn max_written(&self) -> usize {
+        self.max_written
+    }
+
+    /// Convert into the underlying encode error
+    pub fn into_error(self) -> E {
+        self.error
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for PartialEncode<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (up to {} bytes written)",
+            self.error, self.max_written
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: std::error::Error + 'static> std::error::Error for PartialEncode<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
@@ -0,0 +1,83 @@
+//! Compile-time encoded length bounds
+
+use core::marker::PhantomData;
+
+/// Upper bound on the encoded length of an object, known at compile time
+///
+/// This allows static buffers to be sized from a type (ie. `[0u8; Message::MAX_ENCODED_LEN]`)
+/// rather than guessing `N` for [`EncodeExt::encode_buff`][super::EncodeExt::encode_buff].
+///
+/// Implemented for primitives, arrays and [`heapless`] containers, and by `#[derive(Encode)]`
+/// where all fields are bounded.
+/// Unbounded types such as slices, strings and `Vec`s do not implement this trait.
+pub trait MaxEncodedLen {
+    /// Maximum encoded length in bytes
+    const MAX_ENCODED_LEN: usize;
+}
+
+/// Exact encoded length for objects with fixed layouts, known at compile time
+///
+/// Implementations must set [`MaxEncodedLen::MAX_ENCODED_LEN`] to the same value.
+pub trait FixedEncodedLen: MaxEncodedLen {
+    /// Encoded length in bytes
+    const ENCODED_LEN: usize;
+}
+
+/// Helper macro for implementing fixed lengths for primitive types
+macro_rules! impl_fixed_len {
+    ($($t:ty),*) => {
+        $(
+            impl MaxEncodedLen for $t {
+                const MAX_ENCODED_LEN: usize = core::mem::size_of::<$t>();
+            }
+
+            impl FixedEncodedLen for $t {
+                const ENCODED_LEN: usize = core::mem::size_of::<$t>();
+            }
+        )*
+    };
+}
+
+impl_fixed_len!(bool, u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
+
+/// Empty objects encode to nothing
+impl MaxEncodedLen for () {
+    const MAX_ENCODED_LEN: usize = 0;
+}
+
+impl FixedEncodedLen for () {
+    const ENCODED_LEN: usize = 0;
+}
+
+/// Markers encode to nothing
+impl<T> MaxEncodedLen for PhantomData<T> {
+    const MAX_ENCODED_LEN: usize = 0;
+}
+
+impl<T> FixedEncodedLen for PhantomData<T> {
+    const ENCODED_LEN: usize = 0;
+}
+
+/// References share the bounds of the referenced type
+impl<T: MaxEncodedLen> MaxEncodedLen for &T {
+    const MAX_ENCODED_LEN: usize = T::MAX_ENCODED_LEN;
+}
+
+impl<T: FixedEncodedLen> FixedEncodedLen for &T {
+    const ENCODED_LEN: usize = T::ENCODED_LEN;
+}
+
+/// Arrays encode each element in order
+impl<T: MaxEncodedLen, const N: usize> MaxEncodedLen for [T; N] {
+    const MAX_ENCODED_LEN: usize = T::MAX_ENCODED_LEN * N;
+}
+
+impl<T: FixedEncodedLen, const N: usize> FixedEncodedLen for [T; N] {
+    const ENCODED_LEN: usize = T::ENCODED_LEN * N;
+}
+
+/// [`heapless::Vec`]s are bounded by capacity, with length determined by the container
+#[cfg(feature = "heapless")]
+impl<T: MaxEncodedLen, const N: usize> MaxEncodedLen for heapless::Vec<T, N> {
+    const MAX_ENCODED_LEN: usize = T::MAX_ENCODED_LEN * N;
+}
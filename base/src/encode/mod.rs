@@ -1,6 +1,6 @@
 //! [`Encode`] trait implementation
 
-use core::fmt::Debug;
+use core::{fmt::Debug, marker::PhantomData};
 
 use crate::Error;
 
@@ -8,18 +8,67 @@ mod prefixed;
 pub use prefixed::EncodePrefixed;
 
 mod ext;
-pub use ext::EncodeExt;
+pub use ext::{EncodeExt, PartialEncode};
+
+mod cursor;
+pub use cursor::EncodeCursor;
+
+mod max_len;
+pub use max_len::{FixedEncodedLen, MaxEncodedLen};
+
+mod chunked;
+pub use chunked::{
+    encode_window, encode_window_to, ChunkWindow, ChunkedEncoder, EncodeChunked, WindowSink,
+    CHUNK_SCRATCH,
+};
+
+mod sink;
+pub use sink::{
+    count_len, encode_scratch, encode_with_sink, scratch_len, Counter, EncodeSink, FnSink,
+    HashSink, Sink, SCRATCH_LEN_LIMIT, SINK_SCRATCH,
+};
+
+#[cfg(feature = "std")]
+mod vectored;
+#[cfg(feature = "std")]
+pub use vectored::{EncodeVectored, Vectored, VECTORED_COPY_LIMIT};
 
 /// Encode trait implemented for binary encodable objects
+///
+/// Implementations provided by `encdec` (and those generated by `#[derive(Encode)]`)
+/// do not panic on undersized buffers, returning an [`Error`] instead.
+///
+/// These implementations also check [`Encode::encode_len`] against the buffer prior to
+/// writing, so an undersized buffer results in [`Error::BufferTooSmall`] with the buffer
+/// left untouched. The check is made once by the outermost object, with nested objects
+/// written via [`Encode::encode_nested`]. Other errors (ie. from validation or nested user implementations)
+/// may leave partially written output, see [`EncodeExt::try_encode`] where the extent
+/// of written data is required.
 pub trait Encode: Debug {
     /// Error type returned on parse error
     type Error: From<Error> + Debug;
 
     /// Calculate expected encoded length for an object
-    fn encode_len(&self) -> Result<usize, Self::Error>;
+    ///
+    /// Defaults to counting the bytes written by [`Encode::encode_nested`] against scratch
+    /// buffers (see [`scratch_len`]), so implementations relying on the default must not
+    /// call `encode_len` from their encode methods. [`EncodeSink`] implementations should
+    /// prefer [`EncodeSink::counted_len`], counting via a [`Counter`] without a buffer.
+    fn encode_len(&self) -> Result<usize, Self::Error> {
+        scratch_len(|b| self.encode_nested(b))
+    }
 
     /// Encode method writes object data to the provided writer
     fn encode(&self, buff: &mut [u8]) -> Result<usize, Self::Error>;
+
+    /// Encode as part of an enclosing object that has already checked the buffer
+    /// against its encoded length, skipping any up-front length check.
+    ///
+    /// Defaults to [`Encode::encode`], implementations must still return an error
+    /// rather than panic on undersized buffers.
+    fn encode_nested(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        self.encode(buff)
+    }
 }
 
 /// Blanket encode for references to encodable types
@@ -33,6 +82,10 @@ impl<T: Encode> Encode for &T {
     fn encode(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
         <T as Encode>::encode(self, buff)
     }
+
+    fn encode_nested(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        <T as Encode>::encode_nested(self, buff)
+    }
 }
 
 /// Blanket [`Encode`] impl for slices of encodable types
@@ -52,13 +105,23 @@ where
     }
 
     fn encode(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
-        if buff.len() < self.encode_len()? {
-            return Err(Error::Length.into());
+        let n = self.encode_len()?;
+        if buff.len() < n {
+            return Err(Error::BufferTooSmall {
+                needed: n,
+                available: buff.len(),
+                offset: 0,
+            }
+            .into());
         }
 
+        self.encode_nested(buff)
+    }
+
+    fn encode_nested(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
         let mut index = 0;
         for i in 0..self.len() {
-            index += self[i].encode(&mut buff[index..])?
+            index += self[i].encode_nested(buff.get_mut(index..).unwrap_or_default())?
         }
 
         Ok(index)
@@ -75,38 +138,82 @@ where
 
     fn encode_len(&self) -> Result<usize, Self::Error> {
         let mut index = 0;
-        for i in 0..N {
-            index += self[i].encode_len()?;
+        for i in self.iter() {
+            index += i.encode_len()?;
         }
         Ok(index)
     }
 
     fn encode(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
-        if buff.len() < self.encode_len()? {
-            return Err(Error::Length.into());
+        let n = self.encode_len()?;
+        if buff.len() < n {
+            return Err(Error::BufferTooSmall {
+                needed: n,
+                available: buff.len(),
+                offset: 0,
+            }
+            .into());
         }
 
+        self.encode_nested(buff)
+    }
+
+    fn encode_nested(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
         let mut index = 0;
-        for i in 0..N {
-            index += self[i].encode(&mut buff[index..])?
+        for i in self.iter() {
+            index += i.encode_nested(buff.get_mut(index..).unwrap_or_default())?
         }
 
         Ok(index)
     }
 }
 
+/// Zero-length [`Encode`] implementation for unit types
+impl Encode for () {
+    type Error = Error;
+
+    #[inline]
+    fn encode_len(&self) -> Result<usize, Self::Error> {
+        Ok(0)
+    }
+
+    #[inline]
+    fn encode(&self, _buff: &mut [u8]) -> Result<usize, Self::Error> {
+        Ok(0)
+    }
+}
+
+/// Zero-length [`Encode`] implementation for [`PhantomData`] markers
+impl<T: ?Sized> Encode for PhantomData<T> {
+    type Error = Error;
+
+    #[inline]
+    fn encode_len(&self) -> Result<usize, Self::Error> {
+        Ok(0)
+    }
+
+    #[inline]
+    fn encode(&self, _buff: &mut [u8]) -> Result<usize, Self::Error> {
+        Ok(0)
+    }
+}
+
 /// [`Encode`] implementation for [`str`]
 impl Encode for &str {
     type Error = Error;
 
     fn encode_len(&self) -> Result<usize, Self::Error> {
-        Ok(self.as_bytes().len())
+        Ok(self.len())
     }
 
     fn encode(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
         let d = self.as_bytes();
-        if buff.len() < d.encode_len()? {
-            return Err(Error::Length.into());
+        if buff.len() < d.len() {
+            return Err(Error::BufferTooSmall {
+                needed: d.len(),
+                available: buff.len(),
+                offset: 0,
+            });
         }
 
         buff[..d.len()].copy_from_slice(d);
@@ -135,6 +242,12 @@ where
         let b: &[T] = self.as_ref();
         b.encode(buff)
     }
+
+    #[inline]
+    fn encode_nested(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        let b: &[T] = self.as_ref();
+        b.encode_nested(buff)
+    }
 }
 
 /// [`Encode`] implementation for heapless [`heapless::Vec`] containing encodable types
@@ -157,4 +270,27 @@ where
         let b: &[T] = self.as_ref();
         b.encode(buff)
     }
+
+    #[inline]
+    fn encode_nested(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        let b: &[T] = self.as_ref();
+        b.encode_nested(buff)
+    }
+}
+
+/// [`Encode`] implementation for shared [`bytes::Bytes`] buffers
+#[cfg(feature = "bytes")]
+impl Encode for bytes::Bytes {
+    type Error = Error;
+
+    #[inline]
+    fn encode_len(&self) -> Result<usize, Self::Error> {
+        Ok(self.len())
+    }
+
+    #[inline]
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        let b: &[u8] = self.as_ref();
+        b.encode(buff)
+    }
 }
@@ -14,7 +14,7 @@ pub trait EncodePrefixed<P: Encode> {
     fn encode_prefixed(&self, buff: &mut [u8]) -> Result<usize, Self::Error>;
 }
 
-impl<'a, T, P> EncodePrefixed<P> for T
+impl<T, P> EncodePrefixed<P> for T
 where
     T: Encode,
     P: Encode<Error = Error> + FromPrimitive,
@@ -25,12 +25,29 @@ where
     fn encode_prefixed(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
         let mut index = 0;
 
-        // Compute encoded length and write prefix
-        let len = P::from_usize(self.encode_len()?).unwrap();
+        // Compute encoded length
+        let n = self.encode_len()?;
+        let len = match P::from_usize(n) {
+            Some(v) => v,
+            None => return Err(Error::Overflow { offset: 0 }.into()),
+        };
+
+        // Check the prefix and object fit prior to writing
+        let needed = len.encode_len()?.saturating_add(n);
+        if buff.len() < needed {
+            return Err(Error::BufferTooSmall {
+                needed,
+                available: buff.len(),
+                offset: 0,
+            }
+            .into());
+        }
+
+        // Write prefix
         index += len.encode(buff)?;
 
         // Encode object
-        index += self.encode(&mut buff[index..])?;
+        index += self.encode(buff.get_mut(index..).unwrap_or_default())?;
 
         Ok(index)
     }
@@ -0,0 +1,454 @@
+//! Generic output [`Sink`]s for streaming encoded data
+
+use core::{hash::Hasher, marker::PhantomData};
+
+use super::{Encode, EncodeCursor};
+use crate::Error;
+
+/// Maximum encoded length of objects written via the stack scratch buffer in [`encode_scratch`]
+pub const SINK_SCRATCH: usize = 64;
+
+/// Maximum scratch buffer size used by [`scratch_len`] when counting slice encoders on the heap
+pub const SCRATCH_LEN_LIMIT: usize = 64 * 1024;
+
+/// Output sink for encoded data, allowing objects implementing [`EncodeSink`]
+/// to stream into buffers, hashers, byte counters or writers.
+pub trait Sink {
+    /// Write data to the sink, failing where the data can not be accepted
+    fn write_bytes(&mut self, data: &[u8]) -> Result<(), Error>;
+}
+
+/// Encode trait for objects that can be written to a generic [`Sink`].
+///
+/// A single implementation can then encode to buffers (see [`encode_with_sink`]),
+/// compute checksums or hashes over the encoded object, or count encoded bytes.
+/// Implementations for derived types may be generated with `#[derive(EncodeSink)]`.
+///
+/// Derived [`Encode`] implementations are not built on this trait: slice-based
+/// encoding writes fields in place without per-write dispatch, supports fields that
+/// only implement [`Encode`], and lets `enc` overrides write directly into the output.
+/// Types that prefer a single sink implementation can use [`encode_with_sink`] and
+/// [`EncodeSink::counted_len`] to provide [`Encode`].
+pub trait EncodeSink: Encode {
+    /// Encode object to the provided sink, returning the encoded length
+    fn encode_to<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<usize, Self::Error>;
+
+    /// Compute the encoded length by encoding to a [`Counter`], for use as a
+    /// default [`Encode::encode_len`] implementation that can not disagree with
+    /// the encoded output
+    fn counted_len(&self) -> Result<usize, Self::Error> {
+        let mut c = Counter::new();
+        self.encode_to(&mut c)?;
+        Ok(c.count())
+    }
+}
+
+/// Encode an object to a buffer via [`EncodeSink`], for use as a thin
+/// [`Encode::encode`] wrapper.
+///
+/// As with other implementations the encoded length is checked prior to writing,
+/// so an undersized buffer results in [`Error::BufferTooSmall`] with the buffer untouched.
+///
+/// ```
+/// use encdec_base::{Error, encode::{Encode, EncodeSink, Sink, encode_with_sink}};
+///
+/// #[derive(Debug)]
+/// struct Point { x: u16, y: u16 }
+///
+/// impl EncodeSink for Point {
+///     fn encode_to<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<usize, Error> {
+///         Ok(self.x.encode_to(sink)? + self.y.encode_to(sink)?)
+///     }
+/// }
+///
+/// impl Encode for Point {
+///     type Error = Error;
+///
+///     fn encode_len(&self) -> Result<usize, Error> {
+///         self.counted_len()
+///     }
+///
+///     fn encode(&self, buff: &mut [u8]) -> Result<usize, Error> {
+///         encode_with_sink(self, buff)
+///     }
+/// }
+///
+/// let mut buff = [0u8; 4];
+/// assert_eq!(Point{ x: 1, y: 2 }.encode(&mut buff), Ok(4));
+/// assert_eq!(buff, [1, 0, 2, 0]);
+/// ```
+pub fn encode_with_sink<T: EncodeSink + ?Sized>(v: &T, buff: &mut [u8]) -> Result<usize, T::Error> {
+    let n = v.encode_len()?;
+    if buff.len() < n {
+        return Err(Error::BufferTooSmall {
+            needed: n,
+            available: buff.len(),
+            offset: 0,
+        }
+        .into());
+    }
+
+    v.encode_to(&mut EncodeCursor::new(buff))
+}
+
+/// Encode an object via a scratch buffer then write it to the sink,
+/// for [`EncodeSink`] implementations wrapping slice-based encoders.
+///
+/// Objects up to [`SINK_SCRATCH`] bytes are staged on the stack, larger objects
+/// are staged on the heap where the `alloc` feature is enabled and otherwise
+/// return [`Error::BufferTooSmall`].
+pub fn encode_scratch<S: Sink + ?Sized, E: From<Error>>(
+    len: usize,
+    sink: &mut S,
+    f: impl FnOnce(&mut [u8]) -> Result<usize, E>,
+) -> Result<usize, E> {
+    #[cfg(feature = "alloc")]
+    if len > SINK_SCRATCH {
+        let mut s = alloc::vec![0u8; len];
+        let n = f(&mut s)?.min(len);
+        sink.write_bytes(&s[..n])?;
+        return Ok(n);
+    }
+
+    let mut scratch = [0u8; SINK_SCRATCH];
+    let s = match scratch.get_mut(..len) {
+        Some(s) => s,
+        None => {
+            return Err(Error::BufferTooSmall {
+                needed: len,
+                available: SINK_SCRATCH,
+                offset: 0,
+            }
+            .into())
+        }
+    };
+
+    let n = f(s)?.min(len);
+    sink.write_bytes(&s[..n])?;
+
+    Ok(n)
+}
+
+/// Compute the encoded length of a sink-based encoder by running it against a
+/// [`Counter`], for encoders without a length function
+/// (ie. `#[encdec(enc = "..", sink)]` fields without `enc_len`).
+///
+/// ```
+/// use encdec_base::{Error, encode::{count_len, Sink}};
+///
+/// fn enc_be<S: Sink + ?Sized>(v: &u16, sink: &mut S) -> Result<usize, Error> {
+///     sink.write_bytes(&v.to_be_bytes())?;
+///     Ok(2)
+/// }
+///
+/// assert_eq!(count_len(|s| enc_be(&0x0102, s)), Ok(2));
+/// ```
+pub fn count_len<E>(f: impl FnOnce(&mut Counter) -> Result<usize, E>) -> Result<usize, E> {
+    let mut c = Counter::new();
+    f(&mut c)?;
+    Ok(c.count())
+}
+
+/// Compute the encoded length of a slice-based encoder by running it against scratch
+/// buffers, for encoders without a length function (ie. `#[encdec(enc = "..")]` fields
+/// without `enc_len`, or the default [`Encode::encode_len`]).
+///
+/// Encoders are first run against a [`SINK_SCRATCH`] byte stack buffer, then where the
+/// `alloc` feature is enabled against growing heap buffers up to [`SCRATCH_LEN_LIMIT`] bytes,
+/// returning the last encoder error if none succeed. Sink-based encoders should prefer
+/// [`count_len`], which needs no buffer.
+///
+/// ```
+/// use encdec_base::{Error, encode::scratch_len};
+///
+/// fn enc_be(v: &u16, buff: &mut [u8]) -> Result<usize, Error> {
+///     buff.get_mut(..2).ok_or(Error::Length)?.copy_from_slice(&v.to_be_bytes());
+///     Ok(2)
+/// }
+///
+/// assert_eq!(scratch_len(|b| enc_be(&0x0102, b)), Ok(2));
+/// ```
+pub fn scratch_len<E>(mut f: impl FnMut(&mut [u8]) -> Result<usize, E>) -> Result<usize, E> {
+    let mut scratch = [0u8; SINK_SCRATCH];
+    let r = f(&mut scratch);
+
+    #[cfg(feature = "alloc")]
+    if r.is_err() {
+        let mut len = SINK_SCRATCH * 4;
+        loop {
+            let r = f(&mut alloc::vec![0u8; len]);
+            if r.is_ok() || len >= SCRATCH_LEN_LIMIT {
+                return r;
+            }
+            len *= 4;
+        }
+    }
+
+    r
+}
+
+/// [`Sink`] forwarding for mutable references
+impl<S: Sink + ?Sized> Sink for &mut S {
+    fn write_bytes(&mut self, data: &[u8]) -> Result<(), Error> {
+        (**self).write_bytes(data)
+    }
+}
+
+/// [`Sink`] writing into a fixed buffer via [`EncodeCursor`]
+impl Sink for EncodeCursor<'_> {
+    fn write_bytes(&mut self, data: &[u8]) -> Result<(), Error> {
+        if data.len() > self.remaining() {
+            return Err(Error::BufferTooSmall {
+                needed: data.len(),
+                available: self.remaining(),
+                offset: self.position(),
+            });
+        }
+
+        self.write_with(|b| {
+            b[..data.len()].copy_from_slice(data);
+            Ok::<_, Error>(data.len())
+        })?;
+
+        Ok(())
+    }
+}
+
+/// [`Sink`] appending to an [`alloc::vec::Vec`]
+#[cfg(feature = "alloc")]
+impl Sink for alloc::vec::Vec<u8> {
+    fn write_bytes(&mut self, data: &[u8]) -> Result<(), Error> {
+        self.extend_from_slice(data);
+        Ok(())
+    }
+}
+
+/// [`Sink`] appending to a fixed capacity [`heapless::Vec`]
+#[cfg(feature = "heapless")]
+impl<const N: usize> Sink for heapless::Vec<u8, N> {
+    fn write_bytes(&mut self, data: &[u8]) -> Result<(), Error> {
+        let offset = self.len();
+        self.extend_from_slice(data)
+            .map_err(|_| Error::BufferTooSmall {
+                needed: data.len(),
+                available: N - offset,
+                offset,
+            })
+    }
+}
+
+/// [`Sink`] appending to a [`bytes::BytesMut`] buffer
+#[cfg(feature = "bytes")]
+impl Sink for bytes::BytesMut {
+    fn write_bytes(&mut self, data: &[u8]) -> Result<(), Error> {
+        self.extend_from_slice(data);
+        Ok(())
+    }
+}
+
+/// [`Sink`] counting encoded bytes without storing them
+///
+/// ```
+/// use encdec_base::encode::{Counter, EncodeSink};
+///
+/// let mut c = Counter::new();
+/// [1u16, 2, 3].encode_to(&mut c).unwrap();
+/// assert_eq!(c.count(), 6);
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct Counter {
+    count: usize,
+}
+
+impl Counter {
+    /// Create a new [`Counter`]
+    pub const fn new() -> Self {
+        Self { count: 0 }
+    }
+
+    /// Fetch the number of bytes written
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+impl Sink for Counter {
+    fn write_bytes(&mut self, data: &[u8]) -> Result<(), Error> {
+        self.count = self.count.saturating_add(data.len());
+        Ok(())
+    }
+}
+
+/// [`Sink`] feeding encoded bytes to a [`core::hash::Hasher`]
+#[derive(Clone, Debug, Default)]
+pub struct HashSink<H> {
+    hasher: H,
+}
+
+impl<H: Hasher> HashSink<H> {
+    /// Create a new [`HashSink`] over the provided hasher
+    pub fn new(hasher: H) -> Self {
+        Self { hasher }
+    }
+
+    /// Fetch the hash of data written so far
+    pub fn finish(&self) -> u64 {
+        self.hasher.finish()
+    }
+
+    /// Consume the [`HashSink`], returning the underlying hasher
+    pub fn into_inner(self) -> H {
+        self.hasher
+    }
+}
+
+impl<H: Hasher> Sink for HashSink<H> {
+    fn write_bytes(&mut self, data: &[u8]) -> Result<(), Error> {
+        self.hasher.write(data);
+        Ok(())
+    }
+}
+
+/// [`Sink`] calling the provided function with encoded bytes, for digests
+/// or checksum accumulators.
+///
+/// ```
+/// use encdec_base::encode::{EncodeSink, FnSink};
+///
+/// let mut sum = 0u8;
+/// 0x0102u16.encode_to(&mut FnSink::new(|d: &[u8]| {
+///     sum = d.iter().fold(sum, |a, b| a.wrapping_add(*b))
+/// })).unwrap();
+/// assert_eq!(sum, 3);
+/// ```
+#[derive(Clone, Debug)]
+pub struct FnSink<F> {
+    f: F,
+}
+
+impl<F: FnMut(&[u8])> FnSink<F> {
+    /// Create a new [`FnSink`] calling the provided function
+    pub fn new(f: F) -> Self {
+        Self { f }
+    }
+}
+
+impl<F: FnMut(&[u8])> Sink for FnSink<F> {
+    fn write_bytes(&mut self, data: &[u8]) -> Result<(), Error> {
+        (self.f)(data);
+        Ok(())
+    }
+}
+
+/// [`EncodeSink`] for references to sink-encodable types
+impl<T: EncodeSink> EncodeSink for &T {
+    fn encode_to<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<usize, Self::Error> {
+        <T as EncodeSink>::encode_to(self, sink)
+    }
+}
+
+/// [`EncodeSink`] for slices of sink-encodable types
+impl<T> EncodeSink for &[T]
+where
+    T: EncodeSink,
+    <T as Encode>::Error: From<Error>,
+{
+    fn encode_to<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<usize, Self::Error> {
+        let mut n = 0;
+        for v in self.iter() {
+            n += v.encode_to(sink)?;
+        }
+        Ok(n)
+    }
+}
+
+/// [`EncodeSink`] for arrays of sink-encodable types
+impl<T, const N: usize> EncodeSink for [T; N]
+where
+    T: EncodeSink,
+    <T as Encode>::Error: From<Error>,
+{
+    fn encode_to<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<usize, Self::Error> {
+        self.as_slice().encode_to(sink)
+    }
+}
+
+/// [`EncodeSink`] for [`alloc::vec::Vec`]s of sink-encodable types
+#[cfg(feature = "alloc")]
+impl<T> EncodeSink for alloc::vec::Vec<T>
+where
+    T: EncodeSink,
+    <T as Encode>::Error: From<Error>,
+{
+    fn encode_to<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<usize, Self::Error> {
+        self.as_slice().encode_to(sink)
+    }
+}
+
+/// [`EncodeSink`] for [`heapless::Vec`]s of sink-encodable types
+#[cfg(feature = "heapless")]
+impl<T, const N: usize> EncodeSink for heapless::Vec<T, N>
+where
+    T: EncodeSink,
+    <T as Encode>::Error: From<Error>,
+{
+    fn encode_to<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<usize, Self::Error> {
+        self.as_slice().encode_to(sink)
+    }
+}
+
+/// [`EncodeSink`] for string slices, writing string bytes directly
+impl EncodeSink for &str {
+    fn encode_to<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<usize, Self::Error> {
+        sink.write_bytes(self.as_bytes())?;
+        Ok(self.len())
+    }
+}
+
+/// [`EncodeSink`] for shared [`bytes::Bytes`] buffers, writing bytes directly
+#[cfg(feature = "bytes")]
+impl EncodeSink for bytes::Bytes {
+    fn encode_to<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<usize, Self::Error> {
+        sink.write_bytes(self)?;
+        Ok(self.len())
+    }
+}
+
+/// Zero-length [`EncodeSink`] for unit types
+impl EncodeSink for () {
+    fn encode_to<S: Sink + ?Sized>(&self, _sink: &mut S) -> Result<usize, Self::Error> {
+        Ok(0)
+    }
+}
+
+/// Zero-length [`EncodeSink`] for [`PhantomData`] markers
+impl<T: ?Sized> EncodeSink for PhantomData<T> {
+    fn encode_to<S: Sink + ?Sized>(&self, _sink: &mut S) -> Result<usize, Self::Error> {
+        Ok(0)
+    }
+}
+
+/// [`EncodeSink`] for `bool`, writing a single `0` / `1` byte
+impl EncodeSink for bool {
+    fn encode_to<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<usize, Self::Error> {
+        sink.write_bytes(&[*self as u8])?;
+        Ok(1)
+    }
+}
+
+/// [`EncodeSink`] for primitive types, writing little-endian bytes
+macro_rules! impl_sink {
+    ($($t:ty),*) => {
+        $(
+            impl EncodeSink for $t {
+                fn encode_to<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<usize, Self::Error> {
+                    let b = self.to_le_bytes();
+                    sink.write_bytes(&b)?;
+                    Ok(b.len())
+                }
+            }
+        )*
+    };
+}
+
+impl_sink!(u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
@@ -0,0 +1,267 @@
+//! Vectored (scatter-gather) encoding, referencing borrowed payloads in place
+
+use core::{marker::PhantomData, ops::Range};
+
+use alloc::vec::Vec;
+use std::io::{IoSlice, Write};
+
+use super::{Encode, Sink};
+use crate::Error;
+
+/// Payloads shorter than this are copied into the scratch buffer rather than
+/// referenced, avoiding excessive numbers of small segments
+pub const VECTORED_COPY_LIMIT: usize = 64;
+
+/// Segment of vectored output
+#[derive(Clone, Debug)]
+enum Segment<'a> {
+    /// Range in the scratch buffer
+    Scratch(Range<usize>),
+    /// Borrowed payload
+    Borrowed(&'a [u8]),
+}
+
+/// Vectored output, combining small fields encoded into a scratch buffer with
+/// borrowed payloads referenced in place, for use with [`Write::write_vectored`].
+///
+/// ```
+/// use encdec_base::encode::{EncodeVectored, Vectored};
+///
+/// let payload = vec![0xab; 256];
+///
+/// let mut v = Vectored::new();
+/// 0x0102u16.encode_vectored(&mut v).unwrap();
+/// payload.encode_vectored(&mut v).unwrap();
+///
+/// assert_eq!(v.len(), 258);
+/// assert_eq!(v.io_slices().len(), 2);
+///
+/// let mut out = Vec::new();
+/// v.write_to(&mut out).unwrap();
+/// assert_eq!(&out[..3], &[0x02, 0x01, 0xab]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Vectored<'a> {
+    scratch: Vec<u8>,
+    segments: Vec<Segment<'a>>,
+    len: usize,
+}
+
+impl<'a> Vectored<'a> {
+    /// Create a new empty [`Vectored`] output
+    pub fn new() -> Self {
+        Self {
+            scratch: Vec::new(),
+            segments: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Encode an object into the scratch buffer
+    pub fn write<T: Encode + ?Sized>(&mut self, v: &T) -> Result<usize, T::Error> {
+        let n = v.encode_len()?;
+        self.write_with(n, |b| v.encode(b))
+    }
+
+    /// Encode into `len` bytes of scratch buffer using the provided function,
+    /// retaining the returned length
+    pub fn write_with<E>(
+        &mut self,
+        len: usize,
+        f: impl FnOnce(&mut [u8]) -> Result<usize, E>,
+    ) -> Result<usize, E> {
+        let start = self.scratch.len();
+        self.scratch.resize(start + len, 0);
+
+        let n = match f(&mut self.scratch[start..]) {
+            Ok(n) => n.min(len),
+            Err(e) => {
+                self.scratch.truncate(start);
+                return Err(e);
+            }
+        };
+        self.scratch.truncate(start + n);
+
+        self.extend_scratch(start..start + n);
+
+        Ok(n)
+    }
+
+    /// Reference a borrowed payload in place, copying short payloads
+    /// (see [`VECTORED_COPY_LIMIT`])
+    pub fn reference(&mut self, data: &'a [u8]) {
+        if data.len() < VECTORED_COPY_LIMIT {
+            let start = self.scratch.len();
+            self.scratch.extend_from_slice(data);
+            self.extend_scratch(start..self.scratch.len());
+            return;
+        }
+
+        self.segments.push(Segment::Borrowed(data));
+        self.len += data.len();
+    }
+
+    /// Append a scratch range, merging with the previous segment where contiguous
+    fn extend_scratch(&mut self, r: Range<usize>) {
+        if r.is_empty() {
+            return;
+        }
+
+        self.len += r.len();
+
+        match self.segments.last_mut() {
+            Some(Segment::Scratch(p)) if p.end == r.start => p.end = r.end,
+            _ => self.segments.push(Segment::Scratch(r)),
+        }
+    }
+
+    /// Fetch the total encoded length
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether the output is empty
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Fetch [`IoSlice`]s for the encoded output, for [`Write::write_vectored`]
+    pub fn io_slices(&self) -> Vec<IoSlice<'_>> {
+        self.segments
+            .iter()
+            .map(|s| match s {
+                Segment::Scratch(r) => IoSlice::new(&self.scratch[r.clone()]),
+                Segment::Borrowed(b) => IoSlice::new(b),
+            })
+            .collect()
+    }
+
+    /// Write the complete output to the provided writer using vectored writes,
+    /// returning the number of bytes written
+    pub fn write_to<W: Write>(&self, w: &mut W) -> std::io::Result<usize> {
+        let mut slices = self.io_slices();
+        let mut bufs = &mut slices[..];
+
+        while !bufs.is_empty() {
+            match w.write_vectored(bufs) {
+                Ok(0) => return Err(std::io::ErrorKind::WriteZero.into()),
+                Ok(n) => IoSlice::advance_slices(&mut bufs, n),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => (),
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(self.len)
+    }
+
+    /// Clear the output, retaining allocated buffers
+    pub fn clear(&mut self) {
+        self.scratch.clear();
+        self.segments.clear();
+        self.len = 0;
+    }
+}
+
+/// [`Sink`] implementation, copying data into the scratch buffer
+impl Sink for Vectored<'_> {
+    fn write_bytes(&mut self, data: &[u8]) -> Result<(), Error> {
+        let start = self.scratch.len();
+        self.scratch.extend_from_slice(data);
+        self.extend_scratch(start..self.scratch.len());
+        Ok(())
+    }
+}
+
+/// Encode trait for objects that can be encoded to [`Vectored`] output,
+/// referencing borrowed payloads in place rather than copying.
+/// Implementations for derived types may be generated with `#[derive(EncodeVectored)]`.
+pub trait EncodeVectored: Encode {
+    /// Encode object to the provided [`Vectored`] output, returning the encoded length
+    fn encode_vectored<'a>(&'a self, v: &mut Vectored<'a>) -> Result<usize, Self::Error>;
+}
+
+/// [`EncodeVectored`] for references to vectored-encodable types
+impl<T: EncodeVectored> EncodeVectored for &T {
+    fn encode_vectored<'a>(&'a self, v: &mut Vectored<'a>) -> Result<usize, Self::Error> {
+        <T as EncodeVectored>::encode_vectored(self, v)
+    }
+}
+
+/// Referenced [`EncodeVectored`] for byte slices
+impl EncodeVectored for &[u8] {
+    fn encode_vectored<'a>(&'a self, v: &mut Vectored<'a>) -> Result<usize, Self::Error> {
+        v.reference(self);
+        Ok(self.len())
+    }
+}
+
+/// Referenced [`EncodeVectored`] for byte arrays
+impl<const N: usize> EncodeVectored for [u8; N] {
+    fn encode_vectored<'a>(&'a self, v: &mut Vectored<'a>) -> Result<usize, Self::Error> {
+        v.reference(self);
+        Ok(N)
+    }
+}
+
+/// Referenced [`EncodeVectored`] for byte [`Vec`]s
+impl EncodeVectored for Vec<u8> {
+    fn encode_vectored<'a>(&'a self, v: &mut Vectored<'a>) -> Result<usize, Self::Error> {
+        v.reference(self);
+        Ok(self.len())
+    }
+}
+
+/// Referenced [`EncodeVectored`] for fixed capacity byte [`heapless::Vec`]s
+#[cfg(feature = "heapless")]
+impl<const N: usize> EncodeVectored for heapless::Vec<u8, N> {
+    fn encode_vectored<'a>(&'a self, v: &mut Vectored<'a>) -> Result<usize, Self::Error> {
+        v.reference(self);
+        Ok(self.len())
+    }
+}
+
+/// Referenced [`EncodeVectored`] for shared [`bytes::Bytes`] buffers
+#[cfg(feature = "bytes")]
+impl EncodeVectored for bytes::Bytes {
+    fn encode_vectored<'a>(&'a self, v: &mut Vectored<'a>) -> Result<usize, Self::Error> {
+        v.reference(self);
+        Ok(self.len())
+    }
+}
+
+/// Referenced [`EncodeVectored`] for string slices
+impl EncodeVectored for &str {
+    fn encode_vectored<'a>(&'a self, v: &mut Vectored<'a>) -> Result<usize, Self::Error> {
+        v.reference(self.as_bytes());
+        Ok(self.len())
+    }
+}
+
+/// Zero-length [`EncodeVectored`] for unit types
+impl EncodeVectored for () {
+    fn encode_vectored<'a>(&'a self, _v: &mut Vectored<'a>) -> Result<usize, Self::Error> {
+        Ok(0)
+    }
+}
+
+/// Zero-length [`EncodeVectored`] for [`PhantomData`] markers
+impl<T: ?Sized> EncodeVectored for PhantomData<T> {
+    fn encode_vectored<'a>(&'a self, _v: &mut Vectored<'a>) -> Result<usize, Self::Error> {
+        Ok(0)
+    }
+}
+
+/// [`EncodeVectored`] for primitive types, encoded into the scratch buffer
+macro_rules! impl_vectored {
+    ($($t:ty),*) => {
+        $(
+            impl EncodeVectored for $t {
+                fn encode_vectored<'a>(&'a self, v: &mut Vectored<'a>) -> Result<usize, Self::Error> {
+                    v.write(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_vectored!(bool, u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
@@ -1,15 +1,306 @@
 //! Basic encdec [`Error`] type
 
-use core::convert::Infallible;
+use core::{convert::Infallible, fmt};
 
 /// Basic encode/decode error type
+///
+/// Variants carrying an `offset` report the byte offset of the failing object,
+/// relative to the buffer passed to the outermost `encode` / `decode` call where
+/// errors are propagated by derived implementations (see [`Error::offset_by`]).
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
-#[cfg_attr(feature = "thiserror", derive(thiserror::Error))]
 pub enum Error {
     /// Buffer length error in encode or decode
     Length,
     /// Invalid UTF8 in string
     Utf8,
+    /// Output buffer too small to encode an object
+    BufferTooSmall {
+        /// Bytes required to encode the object
+        needed: usize,
+        /// Bytes available in the output buffer
+        available: usize,
+        /// Offset of the object in the output buffer
+        offset: usize,
+    },
+    /// Input buffer ends before an object is complete
+    Incomplete {
+        /// Additional bytes required to decode the object
+        needed: usize,
+        /// Offset of the object in the input buffer
+        offset: usize,
+    },
+    /// Invalid tag or discriminant (ie. a `bool` other than `0` or `1`)
+    InvalidTag {
+        /// Offset of the object in the buffer
+        offset: usize,
+    },
+    /// Invalid or out of range field value
+    InvalidValue {
+        /// Offset of the object in the buffer
+        offset: usize,
+    },
+    /// Checksum does not match object contents, reported by validation hooks
+    /// (see `#[encdec(validate = "...")]`) for objects carrying checksums
+    ChecksumMismatch {
+        /// Offset of the checksum in the buffer
+        offset: usize,
+    },
+    /// Value overflows the encoded representation or available capacity
+    Overflow {
+        /// Offset of the object in the buffer
+        offset: usize,
+    },
+    /// Unexpected bytes remaining following a decoded object
+    TrailingBytes {
+        /// Number of remaining bytes
+        remaining: usize,
+        /// Offset of the first remaining byte
+        offset: usize,
+    },
+    /// Configured [`DecodeLimits`][crate::decode::DecodeLimits] exceeded
+    LimitExceeded {
+        /// Limit that was exceeded
+        limit: Limit,
+        /// Offset of the object in the buffer
+        offset: usize,
+    },
+    /// Output [`Sink`][crate::encode::Sink] failed to accept encoded data
+    Sink {
+        /// Offset of the failed write in the encoded output
+        offset: usize,
+    },
+}
+
+/// Decode limit kinds, reported by [`Error::LimitExceeded`]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Limit {
+    /// Maximum number of elements in a collection
+    Elements,
+    /// Maximum bytes allocated while decoding
+    Alloc,
+    /// Maximum nesting depth
+    Depth,
+    /// Maximum string length
+    StrLen,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Limit::Elements => write!(f, "element"),
+            Limit::Alloc => write!(f, "allocation"),
+            Limit::Depth => write!(f, "depth"),
+            Limit::StrLen => write!(f, "string length"),
+        }
+    }
+}
+
+impl Error {
+    /// Fetch the byte offset associated with an error, if available
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            Error::Length | Error::Utf8 => None,
+            Error::BufferTooSmall { offset, .. }
+            | Error::Incomplete { offset, .. }
+            | Error::InvalidTag { offset }
+            | Error::InvalidValue { offset }
+            | Error::ChecksumMismatch { offset }
+            | Error::Overflow { offset }
+            | Error::TrailingBytes { offset, .. }
+            | Error::LimitExceeded { offset, .. }
+            | Error::Sink { offset } => Some(*offset),
+        }
+    }
+
+    /// Shift the offset associated with an error by `base` bytes, used to
+    /// translate errors from nested objects to the offset in the parent buffer
+    pub fn offset_by(mut self, base: usize) -> Self {
+        match &mut self {
+            Error::Length | Error::Utf8 => (),
+            Error::BufferTooSmall { offset, .. }
+            | Error::Incomplete { offset, .. }
+            | Error::InvalidTag { offset }
+            | Error::InvalidValue { offset }
+            | Error::ChecksumMismatch { offset }
+            | Error::Overflow { offset }
+            | Error::TrailingBytes { offset, .. }
+            | Error::LimitExceeded { offset, .. }
+            | Error::Sink { offset } => *offset += base,
+        }
+        self
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Length => write!(f, "buffer length error"),
+            Error::Utf8 => write!(f, "invalid utf8"),
+            Error::BufferTooSmall {
+                needed,
+                available,
+                offset,
+            } => write!(
+                f,
+                "buffer too small at offset {offset} (needed {needed} bytes, available {available})"
+            ),
+            Error::Incomplete { needed, offset } => write!(
+                f,
+                "incomplete object at offset {offset} (needed {needed} more bytes)"
+            ),
+            Error::InvalidTag { offset } => write!(f, "invalid tag at offset {offset}"),
+            Error::InvalidValue { offset } => write!(f, "invalid value at offset {offset}"),
+            Error::ChecksumMismatch { offset } => {
+                write!(f, "checksum mismatch at offset {offset}")
+            }
+            Error::Overflow { offset } => write!(f, "value overflow at offset {offset}"),
+            Error::TrailingBytes { remaining, offset } => {
+                write!(f, "{remaining} trailing bytes at offset {offset}")
+            }
+            Error::LimitExceeded { limit, offset } => {
+                write!(f, "{limit} limit exceeded at offset {offset}")
+            }
+            Error::Sink { offset } => write!(f, "sink write failed at offset {offset}"),
+        }
+    }
+}
+
+// `core::error::Error` requires Rust 1.81, so error traits are implemented with `std`
+// to retain support for older toolchains
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Classify decode errors, allowing streaming decoders to distinguish incomplete
+/// input (where more data may be read) from malformed input
+pub trait DecodeError {
+    /// Fetch the additional bytes required where the input is incomplete
+    fn incomplete(&self) -> Option<usize>;
+}
+
+impl DecodeError for Error {
+    fn incomplete(&self) -> Option<usize> {
+        match self {
+            Error::Incomplete { needed, .. } => Some(*needed),
+            _ => None,
+        }
+    }
+}
+
+/// Maximum field path depth recorded by [`ContextError`]
+pub const CONTEXT_DEPTH: usize = 8;
+
+/// Error type with struct / field path context, used by derived implementations
+/// with the `#[encdec(context)]` attribute.
+///
+/// Paths are stored without allocation, recording up to [`CONTEXT_DEPTH`] fields
+/// and displayed as `Packet.header.len @ 4: ...`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct ContextError {
+    error: Error,
+    object: Option<&'static str>,
+    path: [&'static str; CONTEXT_DEPTH],
+    depth: usize,
+    truncated: bool,
+}
+
+impl ContextError {
+    /// Fetch the underlying [`Error`]
+    pub fn error(&self) -> Error {
+        self.error
+    }
+
+    /// Fetch the name of the outermost object, if available
+    pub fn object(&self) -> Option<&'static str> {
+        self.object
+    }
+
+    /// Fetch the field path from the outermost object
+    pub fn path(&self) -> &[&'static str] {
+        &self.path[..self.depth]
+    }
+
+    /// Fetch the byte offset associated with an error, if available
+    pub fn offset(&self) -> Option<usize> {
+        self.error.offset()
+    }
+
+    /// Shift the offset associated with an error by `base` bytes
+    pub fn offset_by(mut self, base: usize) -> Self {
+        self.error = self.error.offset_by(base);
+        self
+    }
+
+    /// Set the containing object for errors not associated with a field
+    pub fn in_object(mut self, object: &'static str) -> Self {
+        self.object = Some(object);
+        self
+    }
+
+    /// Prepend a field to the error path, setting the containing object
+    pub fn within(mut self, object: &'static str, field: &'static str) -> Self {
+        self.object = Some(object);
+
+        if self.depth == CONTEXT_DEPTH {
+            self.truncated = true;
+            return self;
+        }
+
+        self.path.copy_within(..self.depth, 1);
+        self.path[0] = field;
+        self.depth += 1;
+
+        self
+    }
+}
+
+impl From<Error> for ContextError {
+    fn from(error: Error) -> Self {
+        Self {
+            error,
+            object: None,
+            path: [""; CONTEXT_DEPTH],
+            depth: 0,
+            truncated: false,
+        }
+    }
+}
+
+impl From<Infallible> for ContextError {
+    fn from(_: Infallible) -> Self {
+        unreachable!()
+    }
+}
+
+impl fmt::Display for ContextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(o) = self.object {
+            write!(f, "{o}")?;
+        }
+        if self.truncated {
+            write!(f, "..")?;
+        }
+        for p in self.path() {
+            write!(f, ".{p}")?;
+        }
+        if let Some(o) = self.offset() {
+            write!(f, " @ {o}")?;
+        }
+
+        write!(f, ": {}", self.error)
+    }
+}
+
+impl DecodeError for ContextError {
+    fn incomplete(&self) -> Option<usize> {
+        self.error.incomplete()
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ContextError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
 }
 
 impl From<Infallible> for Error {
@@ -1,12 +1,15 @@
 //! encdec test and encoding/decoding helpers
 //!
 
-use crate::EncDec;
+use crate::{
+    bits::{BitOrder, BitReader, BitWriter, DecodeBits, EncodeBits},
+    Decode, Encode,
+};
 
 /// Helper for writing encode_decode tests for encodable objects
 pub fn test_encode_decode<'a, T>(buff: &'a mut [u8], v: T)
 where
-    T: EncDec<'a> + PartialEq,
+    T: Encode + Decode<'a, Output = T> + PartialEq,
 {
     let encoded_len = v.encode(buff).unwrap();
     assert_eq!(
@@ -20,3 +23,51 @@ where
     assert!(v == decoded, "value: {:?}, decoded: {:?}", v, decoded);
     assert_eq!(encoded_len, decoded_len, "encode and decode length differ");
 }
+
+/// Helper for writing encode_decode tests for bit-packed objects
+pub fn test_encode_decode_bits<T>(buff: &mut [u8], order: BitOrder, bits: usize, v: T)
+where
+    T: EncodeBits + DecodeBits + PartialEq,
+{
+    let mut w = BitWriter::new(buff, order);
+    w.write_bits(&v, bits).unwrap();
+    assert_eq!(w.position(), bits, "unexpected encoded bit length");
+    let encoded_len = w.finish();
+
+    let mut r = BitReader::new(&buff[..encoded_len], order);
+    let decoded: T = r.read_bits(bits).expect("decode failed");
+
+    assert!(v == decoded, "value: {:?}, decoded: {:?}", v, decoded);
+    assert_eq!(r.position(), bits, "encode and decode length differ");
+}
+
+/// Helper for fuzzing decodable objects, decoding `data` then re-encoding into `buff`
+/// and checking the re-encoded object decodes to the same value.
+///
+/// Returns the decoded object where `data` is decodable.
+pub fn fuzz_roundtrip<'a, T>(buff: &'a mut [u8], data: &'a [u8]) -> Option<T>
+where
+    T: Encode + Decode<'a, Output = T> + PartialEq,
+{
+    // Decode errors are expected for arbitrary input
+    let (v, decoded_len) = T::decode(data).ok()?;
+    assert!(decoded_len <= data.len(), "decoded length exceeds input");
+
+    // Decoded objects must always be re-encodable
+    let encode_len = v
+        .encode_len()
+        .expect("encode_len failed for decoded object");
+    let encoded_len = v.encode(buff).expect("encode failed for decoded object");
+    assert_eq!(
+        encoded_len, encode_len,
+        "actual and expected encode_len differ"
+    );
+
+    let buff: &'a [u8] = buff;
+    let (decoded, n) = T::decode(&buff[..encoded_len]).expect("re-decode failed");
+
+    assert!(v == decoded, "value: {:?}, decoded: {:?}", v, decoded);
+    assert_eq!(n, encoded_len, "encode and decode length differ");
+
+    Some(v)
+}
@@ -0,0 +1,195 @@
+//! [`std::io`] adapters for encoding to writers and decoding from readers
+
+use core::{fmt, marker::PhantomData};
+
+use alloc::vec::Vec;
+use std::io::{ErrorKind, Read, Write};
+
+use crate::{decode::DecodeOwned, encode::Sink, DecodeError, Error};
+
+/// Maximum bytes read per step when decoding from a reader, bounding allocation
+/// to the data actually received rather than lengths claimed by the input
+pub(crate) const READ_CHUNK: usize = 512;
+
+/// Error type for [`std::io`] adapters, wrapping IO and encode / decode errors
+#[derive(Debug)]
+pub enum IoError<E> {
+    /// Underlying IO error
+    Io(std::io::Error),
+    /// Encode or decode error
+    Codec(E),
+}
+
+impl<E> From<std::io::Error> for IoError<E> {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for IoError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IoError::Io(e) => write!(f, "io error: {e}"),
+            IoError::Codec(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for IoError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            IoError::Io(e) => Some(e),
+            IoError::Codec(e) => Some(e),
+        }
+    }
+}
+
+/// Buffered reader wrapper yielding a stream of decoded objects.
+///
+/// Objects must be self-delimiting (ie. fixed size or length prefixed), as decoding
+/// is attempted over whatever data has been buffered. On a decode error buffered
+/// data is discarded, as the stream position is no longer known.
+///
+/// ```
+/// use encdec_base::{io::DecodeReader, encode::Encode};
+///
+/// let data = [0x01, 0x00, 0x02, 0x00];
+///
+/// let r = DecodeReader::<_, u16>::new(&data[..]);
+/// let v: Result<Vec<_>, _> = r.collect();
+/// assert_eq!(v.unwrap(), vec![1, 2]);
+/// ```
+#[derive(Debug)]
+pub struct DecodeReader<R, T> {
+    reader: R,
+    buff: Vec<u8>,
+    _t: PhantomData<T>,
+}
+
+impl<R: Read, T> DecodeReader<R, T>
+where
+    T: DecodeOwned<Output = T>,
+    <T as DecodeOwned>::Error: DecodeError,
+{
+    /// Create a new [`DecodeReader`] over the provided reader
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buff: Vec::new(),
+            _t: PhantomData,
+        }
+    }
+
+    /// Fetch buffered data not yet decoded
+    pub fn buffered(&self) -> &[u8] {
+        &self.buff
+    }
+
+    /// Consume the [`DecodeReader`], returning the underlying reader
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+/// [`Iterator`] implementation, returning `None` once the reader is exhausted
+impl<R: Read, T> Iterator for DecodeReader<R, T>
+where
+    T: DecodeOwned<Output = T>,
+    <T as DecodeOwned>::Error: DecodeError,
+{
+    type Item = Result<T, IoError<<T as DecodeOwned>::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // Attempt to decode buffered data
+            if !self.buff.is_empty() {
+                match T::decode_owned(&self.buff) {
+                    Ok((v, n)) => {
+                        self.buff.drain(..n.min(self.buff.len()));
+                        return Some(Ok(v));
+                    }
+                    Err(e) if e.incomplete().is_none() => {
+                        self.buff.clear();
+                        return Some(Err(IoError::Codec(e)));
+                    }
+                    Err(_) => (),
+                }
+            }
+
+            // Read more data
+            let mut chunk = [0u8; READ_CHUNK];
+            match self.reader.read(&mut chunk) {
+                Ok(0) if self.buff.is_empty() => return None,
+                Ok(0) => {
+                    self.buff.clear();
+                    return Some(Err(IoError::Io(ErrorKind::UnexpectedEof.into())));
+                }
+                Ok(n) => self.buff.extend_from_slice(&chunk[..n]),
+                Err(e) if e.kind() == ErrorKind::Interrupted => (),
+                Err(e) => return Some(Err(IoError::Io(e))),
+            }
+        }
+    }
+}
+
+/// [`Sink`] writing encoded data to a [`std::io::Write`]r.
+///
+/// IO errors are reported to encoders as [`Error::Sink`], with the underlying
+/// error retained and available via [`IoSink::take_error`].
+///
+/// ```
+/// use encdec_base::{io::IoSink, encode::EncodeSink};
+///
+/// let mut s = IoSink::new(Vec::new());
+/// 0x0102u16.encode_to(&mut s).unwrap();
+/// assert_eq!(s.into_inner(), vec![0x02, 0x01]);
+/// ```
+#[derive(Debug)]
+pub struct IoSink<W> {
+    writer: W,
+    written: usize,
+    error: Option<std::io::Error>,
+}
+
+impl<W: Write> IoSink<W> {
+    /// Create a new [`IoSink`] over the provided writer
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            written: 0,
+            error: None,
+        }
+    }
+
+    /// Fetch the number of bytes written
+    pub fn written(&self) -> usize {
+        self.written
+    }
+
+    /// Take the last IO error, if any
+    pub fn take_error(&mut self) -> Option<std::io::Error> {
+        self.error.take()
+    }
+
+    /// Consume the [`IoSink`], returning the underlying writer
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> Sink for IoSink<W> {
+    fn write_bytes(&mut self, data: &[u8]) -> Result<(), Error> {
+        match self.writer.write_all(data) {
+            Ok(()) => {
+                self.written += data.len();
+                Ok(())
+            }
+            Err(e) => {
+                self.error = Some(e);
+                Err(Error::Sink {
+                    offset: self.written,
+                })
+            }
+        }
+    }
+}
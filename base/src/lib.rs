@@ -20,12 +20,31 @@ pub mod decode;
 use decode::{Decode, DecodeOwned};
 
 mod error;
-pub use error::Error;
+pub use error::{ContextError, DecodeError, Error, Limit, CONTEXT_DEPTH};
 
 pub mod primitives;
 
+pub mod bits;
+
 pub mod helpers;
 
+pub mod stuffing;
+
+#[cfg(feature = "std")]
+pub mod io;
+
+#[cfg(feature = "embedded-io")]
+pub mod embedded;
+
+#[cfg(feature = "tokio-util")]
+pub mod codec;
+
+#[cfg(feature = "arbitrary")]
+pub use arbitrary;
+
+#[cfg(feature = "bytes")]
+pub use bytes;
+
 /// Composite trait requiring an object is reversibly encodable and decodable
 /// into borrowed types, useful for simplifying type bounds / generics.
 ///
@@ -9,26 +9,43 @@ use crate::{DecodeOwned, Encode, Error};
 trait FixedEncDec: Sized {
     const N: usize;
 
-    fn e(&self, buff: &mut [u8]) -> ();
+    fn e(&self, buff: &mut [u8]);
     fn d(buff: &[u8]) -> Self;
 }
 
 /// Helper macro for implementing primitive encode / decode
 macro_rules! impl_encdec {
     ($t:ty, $n:literal, $d:expr, $e:expr) => {
+        impl FixedEncDec for $t {
+            const N: usize = $n;
+
+            #[inline]
+            fn e(&self, buff: &mut [u8]) {
+                $e(buff, *self)
+            }
+
+            #[inline]
+            fn d(buff: &[u8]) -> Self {
+                $d(buff)
+            }
+        }
+
         impl DecodeOwned for $t {
             type Output = $t;
             type Error = Error;
 
             #[inline]
             fn decode_owned(buff: &[u8]) -> Result<(Self::Output, usize), Self::Error> {
-                if buff.len() < $n {
-                    return Err(Error::Length);
-                }
+                let n = <$t as FixedEncDec>::N;
 
-                let v = $d(&buff[..$n]);
+                if buff.len() < n {
+                    return Err(Error::Incomplete {
+                        needed: n - buff.len(),
+                        offset: 0,
+                    });
+                }
 
-                Ok((v, $n))
+                Ok((<$t>::d(&buff[..n]), n))
             }
         }
 
@@ -37,18 +54,24 @@ macro_rules! impl_encdec {
 
             #[inline]
             fn encode_len(&self) -> Result<usize, Self::Error> {
-                Ok($n)
+                Ok(<$t as FixedEncDec>::N)
             }
 
             #[inline]
             fn encode(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
-                if buff.len() < $n {
-                    return Err(Error::Length);
+                let n = <$t as FixedEncDec>::N;
+
+                if buff.len() < n {
+                    return Err(Error::BufferTooSmall {
+                        needed: n,
+                        available: buff.len(),
+                        offset: 0,
+                    });
                 }
 
-                $e(&mut buff[..$n], *self);
+                self.e(&mut buff[..n]);
 
-                Ok($n)
+                Ok(n)
             }
         }
     };
@@ -84,3 +107,47 @@ fn put_u8(buff: &mut [u8], val: u8) {
 fn put_i8(buff: &mut [u8], val: i8) {
     buff[0] = val as u8;
 }
+
+/// [`DecodeOwned`] for `bool`, encoded as a single `0` / `1` byte
+impl DecodeOwned for bool {
+    type Output = bool;
+    type Error = Error;
+
+    #[inline]
+    fn decode_owned(buff: &[u8]) -> Result<(Self::Output, usize), Self::Error> {
+        match buff.first() {
+            Some(0) => Ok((false, 1)),
+            Some(1) => Ok((true, 1)),
+            Some(_) => Err(Error::InvalidTag { offset: 0 }),
+            None => Err(Error::Incomplete {
+                needed: 1,
+                offset: 0,
+            }),
+        }
+    }
+}
+
+/// [`Encode`] for `bool`, encoded as a single `0` / `1` byte
+impl Encode for bool {
+    type Error = Error;
+
+    #[inline]
+    fn encode_len(&self) -> Result<usize, Self::Error> {
+        Ok(1)
+    }
+
+    #[inline]
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        match buff.first_mut() {
+            Some(b) => {
+                *b = *self as u8;
+                Ok(1)
+            }
+            None => Err(Error::BufferTooSmall {
+                needed: 1,
+                available: 0,
+                offset: 0,
+            }),
+        }
+    }
+}
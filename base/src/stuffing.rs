@@ -0,0 +1,565 @@
+//! Byte-stuffing frame layers (COBS, SLIP and HDLC-style escaping) for
+//! delimiting encoded objects on serial links.
+//!
+//! Frames are stuffed and unstuffed in place where possible, with
+//! [`encode_frame`] encoding an object directly into the output buffer prior
+//! to stuffing, and [`decode_frame`] unstuffing a received frame in place prior
+//! to decoding. [`FrameSplitter`] locates frame boundaries in received streams.
+//!
+//! ```
+//! use encdec_base::stuffing::{decode_frame, encode_frame, Cobs, Stuffing};
+//!
+//! let mut buff = [0u8; 16];
+//! let n = encode_frame::<Cobs, _>(&0x00110022u32, &mut buff).unwrap();
+//! assert_eq!(&buff[..n], &[0x02, 0x22, 0x02, 0x11, 0x01, 0x00]);
+//!
+//! let v = decode_frame::<Cobs, u32>(&mut buff[..n]).unwrap();
+//! assert_eq!(v, 0x00110022);
+//! ```
+
+use core::marker::PhantomData;
+
+use crate::{
+    decode::{Decode, DecodeOwned, StreamBuffer},
+    encode::Encode,
+    Error,
+};
+
+/// Byte-stuffing algorithm used to delimit frames
+pub trait Stuffing {
+    /// Byte delimiting frames, which never appears within stuffed frame contents
+    const DELIMITER: u8;
+
+    /// Maximum stuffed frame length (including delimiters) for `len` bytes of data
+    fn max_len(len: usize) -> usize;
+
+    /// Stuff data located at `buff[start..]` into a frame at the start of the
+    /// buffer (including delimiters), returning the frame length.
+    ///
+    /// `start` must be at least `max_len(n) - n` for `n` bytes of data,
+    /// ensuring stuffed output never overtakes the unread data.
+    fn stuff_in_place(buff: &mut [u8], start: usize) -> Result<usize, Error>;
+
+    /// Unstuff frame contents (excluding delimiters) in place,
+    /// returning the unstuffed length
+    fn unstuff_in_place(buff: &mut [u8]) -> Result<usize, Error>;
+}
+
+/// Check sufficient space precedes data for in-place stuffing
+fn check_space<S: Stuffing>(buff: &[u8], start: usize) -> Result<usize, Error> {
+    let n = buff.len().saturating_sub(start);
+    let needed = S::max_len(n);
+
+    if buff.len() < needed || start > buff.len() {
+        return Err(Error::BufferTooSmall {
+            needed,
+            available: buff.len(),
+            offset: 0,
+        });
+    }
+
+    Ok(n)
+}
+
+/// Consistent Overhead Byte Stuffing (COBS), with `0x00` delimiters
+/// following each frame
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct Cobs;
+
+impl Stuffing for Cobs {
+    const DELIMITER: u8 = 0x00;
+
+    fn max_len(len: usize) -> usize {
+        len + len / 254 + 2
+    }
+
+    fn stuff_in_place(buff: &mut [u8], start: usize) -> Result<usize, Error> {
+        check_space::<Self>(buff, start)?;
+
+        let (mut r, mut w) = (start, 1);
+        let (mut code_index, mut code) = (0, 1u8);
+
+        while r < buff.len() {
+            let b = buff[r];
+            r += 1;
+
+            if b != 0 {
+                buff[w] = b;
+                w += 1;
+                code += 1;
+            }
+
+            // Close blocks at zeros or once full
+            if b == 0 || code == 0xff {
+                buff[code_index] = code;
+                code_index = w;
+                w += 1;
+                code = 1;
+            }
+        }
+
+        buff[code_index] = code;
+        buff[w] = Self::DELIMITER;
+
+        Ok(w + 1)
+    }
+
+    fn unstuff_in_place(buff: &mut [u8]) -> Result<usize, Error> {
+        let (mut r, mut w) = (0, 0);
+
+        while r < buff.len() {
+            let code = buff[r] as usize;
+            if code == 0 {
+                return Err(Error::InvalidValue { offset: r });
+            }
+            r += 1;
+
+            // Blocks extending past the end of the data indicate a partial frame
+            let n = code - 1;
+            if r + n > buff.len() {
+                return Err(Error::Incomplete {
+                    needed: r + n - buff.len(),
+                    offset: r - 1,
+                });
+            }
+
+            buff.copy_within(r..r + n, w);
+            r += n;
+            w += n;
+
+            // Blocks other than full blocks are terminated by an implied zero
+            if code != 0xff && r < buff.len() {
+                buff[w] = 0;
+                w += 1;
+            }
+        }
+
+        Ok(w)
+    }
+}
+
+/// Escape data at `buff[start..]` into a delimited frame at the start of the buffer
+fn escape_in_place(
+    buff: &mut [u8],
+    start: usize,
+    delimiter: u8,
+    esc: u8,
+    escape: impl Fn(u8) -> Option<u8>,
+) -> usize {
+    buff[0] = delimiter;
+    let mut w = 1;
+
+    for r in start..buff.len() {
+        let b = buff[r];
+
+        match escape(b) {
+            Some(e) => {
+                buff[w] = esc;
+                buff[w + 1] = e;
+                w += 2;
+            }
+            None => {
+                buff[w] = b;
+                w += 1;
+            }
+        }
+    }
+
+    buff[w] = delimiter;
+    w + 1
+}
+
+/// Unescape frame contents in place
+fn unescape_in_place(
+    buff: &mut [u8],
+    delimiter: u8,
+    esc: u8,
+    unescape: impl Fn(u8) -> Option<u8>,
+) -> Result<usize, Error> {
+    let (mut r, mut w) = (0, 0);
+
+    while r < buff.len() {
+        let b = match buff[r] {
+            v if v == delimiter => return Err(Error::InvalidValue { offset: r }),
+            v if v == esc => {
+                r += 1;
+                match buff.get(r).copied().and_then(&unescape) {
+                    Some(v) => v,
+                    None => return Err(Error::InvalidValue { offset: r - 1 }),
+                }
+            }
+            v => v,
+        };
+
+        buff[w] = b;
+        r += 1;
+        w += 1;
+    }
+
+    Ok(w)
+}
+
+/// Serial Line IP (SLIP, [RFC 1055](https://www.rfc-editor.org/rfc/rfc1055)) framing,
+/// with `0xC0` delimiters preceding and following each frame
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct Slip;
+
+impl Slip {
+    /// Frame delimiter
+    pub const END: u8 = 0xc0;
+    /// Escape byte
+    pub const ESC: u8 = 0xdb;
+    /// Escaped delimiter
+    pub const ESC_END: u8 = 0xdc;
+    /// Escaped escape byte
+    pub const ESC_ESC: u8 = 0xdd;
+}
+
+impl Stuffing for Slip {
+    const DELIMITER: u8 = Self::END;
+
+    fn max_len(len: usize) -> usize {
+        len * 2 + 2
+    }
+
+    fn stuff_in_place(buff: &mut [u8], start: usize) -> Result<usize, Error> {
+        check_space::<Self>(buff, start)?;
+
+        Ok(escape_in_place(
+            buff,
+            start,
+            Self::END,
+            Self::ESC,
+            |b| match b {
+                Self::END => Some(Self::ESC_END),
+                Self::ESC => Some(Self::ESC_ESC),
+                _ => None,
+            },
+        ))
+    }
+
+    fn unstuff_in_place(buff: &mut [u8]) -> Result<usize, Error> {
+        unescape_in_place(buff, Self::END, Self::ESC, |b| match b {
+            Self::ESC_END => Some(Self::END),
+            Self::ESC_ESC => Some(Self::ESC),
+            _ => None,
+        })
+    }
+}
+
+/// HDLC-style asynchronous framing, with `0x7E` flags preceding and following
+/// each frame and escaped bytes XORed with `0x20`.
+///
+/// Only byte-stuffing is applied, frame check sequences may be included as
+/// fields of the encoded object.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct Hdlc;
+
+impl Hdlc {
+    /// Frame delimiter
+    pub const FLAG: u8 = 0x7e;
+    /// Escape byte
+    pub const ESC: u8 = 0x7d;
+    /// Value XORed with escaped bytes
+    pub const XOR: u8 = 0x20;
+}
+
+impl Stuffing for Hdlc {
+    const DELIMITER: u8 = Self::FLAG;
+
+    fn max_len(len: usize) -> usize {
+        len * 2 + 2
+    }
+
+    fn stuff_in_place(buff: &mut [u8], start: usize) -> Result<usize, Error> {
+        check_space::<Self>(buff, start)?;
+
+        Ok(escape_in_place(
+            buff,
+            start,
+            Self::FLAG,
+            Self::ESC,
+            |b| match b {
+                Self::FLAG | Self::ESC => Some(b ^ Self::XOR),
+                _ => None,
+            },
+        ))
+    }
+
+    fn unstuff_in_place(buff: &mut [u8]) -> Result<usize, Error> {
+        unescape_in_place(buff, Self::FLAG, Self::ESC, |b| match b ^ Self::XOR {
+            v @ (Self::FLAG | Self::ESC) => Some(v),
+            _ => None,
+        })
+    }
+}
+
+/// Stuff data into a frame in the provided buffer, returning the frame length.
+///
+/// The buffer must be at least [`Stuffing::max_len`] bytes.
+pub fn stuff<S: Stuffing>(data: &[u8], buff: &mut [u8]) -> Result<usize, Error> {
+    let max = S::max_len(data.len());
+    if buff.len() < max {
+        return Err(Error::BufferTooSmall {
+            needed: max,
+            available: buff.len(),
+            offset: 0,
+        });
+    }
+
+    let start = max - data.len();
+    buff[start..max].copy_from_slice(data);
+
+    S::stuff_in_place(&mut buff[..max], start)
+}
+
+/// Unstuff frame contents (excluding delimiters) into the provided buffer,
+/// returning the unstuffed length.
+///
+/// The buffer must be at least the length of the stuffed data.
+pub fn unstuff<S: Stuffing>(data: &[u8], buff: &mut [u8]) -> Result<usize, Error> {
+    if buff.len() < data.len() {
+        return Err(Error::BufferTooSmall {
+            needed: data.len(),
+            available: buff.len(),
+            offset: 0,
+        });
+    }
+
+    buff[..data.len()].copy_from_slice(data);
+
+    S::unstuff_in_place(&mut buff[..data.len()])
+}
+
+/// Encode an object as a stuffed frame (including delimiters), returning the frame length.
+///
+/// The object is encoded into the tail of the buffer then stuffed in place, so the
+/// buffer must be at least [`Stuffing::max_len`] of the encoded length.
+pub fn encode_frame<S: Stuffing, T: Encode + ?Sized>(
+    value: &T,
+    buff: &mut [u8],
+) -> Result<usize, T::Error> {
+    let n = value.encode_len()?;
+    let max = S::max_len(n);
+
+    if buff.len() < max {
+        return Err(Error::BufferTooSmall {
+            needed: max,
+            available: buff.len(),
+            offset: 0,
+        }
+        .into());
+    }
+
+    let start = max - n;
+    let n = value.encode(&mut buff[start..max])?;
+
+    Ok(S::stuff_in_place(&mut buff[..start + n], start)?)
+}
+
+/// Trim delimiters from either end of a frame
+fn trim<S: Stuffing>(frame: &mut [u8]) -> &mut [u8] {
+    let start = frame
+        .iter()
+        .position(|b| *b != S::DELIMITER)
+        .unwrap_or(frame.len());
+    let end = frame
+        .iter()
+        .rposition(|b| *b != S::DELIMITER)
+        .map(|i| i + 1)
+        .unwrap_or(start);
+
+    &mut frame[start..end]
+}
+
+/// Check an unstuffed frame was fully consumed
+fn check_trailing(decoded: usize, len: usize) -> Result<(), Error> {
+    match decoded < len {
+        true => Err(Error::TrailingBytes {
+            remaining: len - decoded,
+            offset: decoded,
+        }),
+        false => Ok(()),
+    }
+}
+
+/// Decode an object from a stuffed frame (with or without delimiters),
+/// unstuffing the frame in place.
+///
+/// Frames must contain exactly one encoded object.
+pub fn decode_frame<'a, S: Stuffing, T: Decode<'a>>(
+    frame: &'a mut [u8],
+) -> Result<T::Output, T::Error> {
+    let frame = trim::<S>(frame);
+    let n = S::unstuff_in_place(frame)?;
+
+    let data: &'a [u8] = frame;
+    let (v, decoded) = T::decode(&data[..n])?;
+    check_trailing(decoded, n)?;
+
+    Ok(v)
+}
+
+/// Streaming frame splitter, accumulating received data in a [`StreamBuffer`]
+/// and yielding unstuffed frames as delimiters are received.
+///
+/// Empty frames (ie. consecutive delimiters) are skipped. Where a frame
+/// overflows a fixed capacity buffer an error is returned and data is
+/// discarded until the next delimiter.
+///
+/// ```
+/// # #[cfg(feature = "alloc")]
+/// # fn main() {
+/// use encdec_base::stuffing::{FrameSplitter, Slip};
+///
+/// let mut s = FrameSplitter::<Slip, Vec<u8>>::new();
+/// let mut frame = [0u8; 16];
+///
+/// s.push(&[0xc0, 0x01, 0xdb]);
+/// assert_eq!(s.poll(&mut frame), Ok(None));
+///
+/// s.push(&[0xdc, 0x02, 0xc0, 0xc0, 0x03]);
+/// assert_eq!(s.poll(&mut frame), Ok(Some(3)));
+/// assert_eq!(&frame[..3], &[0x01, 0xc0, 0x02]);
+/// assert_eq!(s.poll(&mut frame), Ok(None));
+/// # }
+/// # #[cfg(not(feature = "alloc"))]
+/// # fn main() {}
+/// ```
+#[derive(Debug)]
+pub struct FrameSplitter<S, B> {
+    buff: B,
+    scanned: usize,
+    discard: bool,
+    _s: PhantomData<S>,
+}
+
+impl<S: Stuffing, B: StreamBuffer> FrameSplitter<S, B> {
+    /// Create a new [`FrameSplitter`] with a default buffer
+    pub fn new() -> Self
+    where
+        B: Default,
+    {
+        Self::with_buffer(B::default())
+    }
+
+    /// Create a new [`FrameSplitter`] using the provided buffer
+    pub fn with_buffer(buff: B) -> Self {
+        Self {
+            buff,
+            scanned: 0,
+            discard: false,
+            _s: PhantomData,
+        }
+    }
+
+    /// Push a chunk of received data, returning the number of bytes accepted
+    /// (which may be less than provided where the buffer is full)
+    pub fn push(&mut self, data: &[u8]) -> usize {
+        // Skip data following an overflow until the next delimiter
+        if self.discard {
+            match data.iter().position(|b| *b == S::DELIMITER) {
+                Some(i) => {
+                    self.discard = false;
+                    return i + self.buff.push(&data[i..]);
+                }
+                None => return data.len(),
+            }
+        }
+
+        self.buff.push(data)
+    }
+
+    /// Locate the next complete frame in buffered data, returning the
+    /// stuffed frame length (excluding the delimiter)
+    fn next_frame(&mut self) -> Result<Option<usize>, Error> {
+        loop {
+            let data = self.buff.data();
+
+            let i = match data[self.scanned..].iter().position(|b| *b == S::DELIMITER) {
+                Some(i) => self.scanned + i,
+                None => {
+                    let len = data.len();
+                    self.scanned = len;
+
+                    // Discard frames exceeding the buffer capacity
+                    if self.buff.capacity() == Some(len) {
+                        self.clear();
+                        self.discard = true;
+
+                        return Err(Error::BufferTooSmall {
+                            needed: len + 1,
+                            available: len,
+                            offset: 0,
+                        });
+                    }
+
+                    return Ok(None);
+                }
+            };
+
+            self.scanned = 0;
+
+            // Skip empty frames
+            if i == 0 {
+                self.buff.consume(1);
+                continue;
+            }
+
+            return Ok(Some(i));
+        }
+    }
+
+    /// Poll for the next frame, unstuffing into the provided buffer and
+    /// returning the unstuffed length.
+    ///
+    /// The buffer must be at least the stuffed frame length.
+    pub fn poll(&mut self, buff: &mut [u8]) -> Result<Option<usize>, Error> {
+        let n = match self.next_frame()? {
+            Some(n) => n,
+            None => return Ok(None),
+        };
+
+        let r = unstuff::<S>(&self.buff.data()[..n], buff);
+        self.buff.consume(n + 1);
+
+        r.map(Some)
+    }
+
+    /// Poll for and decode the next frame, using the provided buffer for unstuffing.
+    ///
+    /// Frames must contain exactly one encoded object.
+    pub fn poll_decode<T: DecodeOwned>(
+        &mut self,
+        buff: &mut [u8],
+    ) -> Result<Option<T::Output>, T::Error> {
+        let n = match self.poll(buff)? {
+            Some(n) => n,
+            None => return Ok(None),
+        };
+
+        let (v, decoded) = T::decode_owned(&buff[..n])?;
+        check_trailing(decoded, n)?;
+
+        Ok(Some(v))
+    }
+
+    /// Fetch buffered data not yet split
+    pub fn buffered(&self) -> &[u8] {
+        self.buff.data()
+    }
+
+    /// Discard buffered data, resetting the splitter
+    pub fn clear(&mut self) {
+        let n = self.buff.data().len();
+        self.buff.consume(n);
+        self.scanned = 0;
+        self.discard = false;
+    }
+}
+
+impl<S: Stuffing, B: StreamBuffer + Default> Default for FrameSplitter<S, B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -12,10 +12,20 @@
 #![no_std]
 
 // Re-export base traits
-pub use encdec_base::{EncDec, EncDecOwned, Error};
+pub use encdec_base::{ContextError, DecodeError, EncDec, EncDecOwned, Error, Limit};
+
+// Re-export arbitrary for derived implementations
+#[cfg(feature = "arbitrary")]
+#[doc(hidden)]
+pub use encdec_base::arbitrary;
+
+// Re-export bytes for derived implementations
+#[cfg(feature = "bytes")]
+#[doc(hidden)]
+pub use encdec_base::bytes;
 
 // Re-export traits from modules here
-pub use crate::decode::{Decode, DecodeExt, DecodeOwned};
+pub use crate::decode::{Decode, DecodeExt, DecodeOwned, DecodeOwnedExt};
 pub use crate::encode::{Encode, EncodeExt};
 
 pub mod encode {
@@ -57,10 +67,41 @@ pub mod encode {
     //!    }
     //! }
     //! ```
+    //!
+    //! ## Cursors
+    //!
+    //! [`EncodeCursor`] tracks the write position for hand-written implementations,
+    //! handling bounds and offsets between fields.
+    //!
+    //! ```
+    //! # use encdec::{Encode, Error, encode::EncodeCursor};
+    //! # #[derive(Debug, PartialEq)]
+    //! # struct Something { a: u8, b: u16, c: [u8; 3] }
+    //! impl Encode for Something {
+    //!     type Error = Error;
+    //!
+    //!     fn encode_len(&self) -> Result<usize, Self::Error> {
+    //!         Ok(1 + 2 + 3)
+    //!     }
+    //!
+    //!     fn encode(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
+    //!         let mut c = EncodeCursor::new(buff);
+    //!
+    //!         c.write(&self.a)?;
+    //!         c.write(&self.b)?;
+    //!         c.write(&self.c)?;
+    //!
+    //!         Ok(c.position())
+    //!     }
+    //! }
+    //! ```
 
     pub use encdec_base::encode::*;
 
-    pub use crate::derive::Encode;
+    pub use crate::derive::{Encode, EncodeChunked, EncodeSink};
+
+    #[cfg(feature = "std")]
+    pub use crate::derive::EncodeVectored;
 }
 
 pub mod decode {
@@ -134,10 +175,97 @@ pub mod decode {
     //!    }
     //! }
     //! ```
+    //!
+    //! ## Cursors
+    //!
+    //! [`DecodeCursor`] tracks the read position for hand-written implementations,
+    //! handling bounds and offsets between fields.
+    //!
+    //! ```
+    //! # use encdec::{DecodeOwned, Error, decode::DecodeCursor};
+    //! # #[derive(Debug, PartialEq)]
+    //! # struct SomethingOwned { a: u8, b: u16, c: [u8; 3] }
+    //! impl DecodeOwned for SomethingOwned {
+    //!     type Output = Self;
+    //!     type Error = Error;
+    //!
+    //!     fn decode_owned(buff: &[u8]) -> Result<(Self::Output, usize), Self::Error> {
+    //!         let mut c = DecodeCursor::new(buff);
+    //!
+    //!         let a = c.read::<u8>()?;
+    //!         let b = c.read::<u16>()?;
+    //!         let v = c.read::<[u8; 3]>()?;
+    //!
+    //!         Ok((Self{ a, b, c: v }, c.position()))
+    //!     }
+    //! }
+    //!
+    //! assert!(SomethingOwned::decode_owned(&[0u8; 5]).is_err());
+    //! ```
+    //!
+    //! ## Limits
+    //!
+    //! When decoding untrusted input, [`DecodeLimits`] bound the number of
+    //! collection elements, total allocation, nesting depth and string length.
+    //! Limits are enforced by built-in collections and derived decoders.
+    //!
+    //! ```
+    //! # #[cfg(feature = "alloc")]
+    //! # fn main() {
+    //! # use encdec::{Decode, DecodeOwned, Error, Limit, decode::DecodeLimits};
+    //! #[derive(Debug, PartialEq, DecodeOwned)]
+    //! struct Items {
+    //!     values: Vec<u16>,
+    //! }
+    //!
+    //! let mut limits = DecodeLimits::new().with_max_elements(2);
+    //!
+    //! let r = Items::decode_limited(&[1, 0, 2, 0, 3, 0], &mut limits);
+    //! assert_eq!(r, Err(Error::LimitExceeded{ limit: Limit::Elements, offset: 4 }));
+    //! # }
+    //! # #[cfg(not(feature = "alloc"))]
+    //! # fn main() {}
+    //! ```
+    //!
+    //! ## Split input
+    //!
+    //! `#[derive(DecodeSplit)]` implements [`DecodeSplit`] for decoding from non-contiguous
+    //! input such as a wrapped ring buffer ([`SplitBuf`]). Owned fields are decoded across the
+    //! split, with borrowed (`length` delimited) fields copied into [`Scratch`] space only
+    //! where they span the split.
+    //!
+    //! ```
+    //! # use encdec::{Decode, DecodeOwned, decode::{DecodeSplit, Scratch, SplitBuf}};
+    //! #[derive(Debug, PartialEq, Decode, DecodeSplit)]
+    //! struct Frame<'a> {
+    //!     id: u16,
+    //!     len: u8,
+    //!     #[encdec(length = "len")]
+    //!     data: &'a [u8],
+    //! }
+    //!
+    //! // Frame wrapped at the end of a ring buffer
+    //! let ring = [0xbb, 0xcc, 0x00, 0x00, 0x01, 0x02, 0x03, 0xaa];
+    //! let buff = SplitBuf::from_ring(&ring, 4, 6);
+    //!
+    //! let mut scratch = [0u8; 8];
+    //! let (f, n) = Frame::decode_split(buff, &mut Scratch::new(&mut scratch)).unwrap();
+    //! assert_eq!(f, Frame{ id: 0x0201, len: 3, data: &[0xaa, 0xbb, 0xcc] });
+    //! assert_eq!(n, 6);
+    //! ```
+    //!
+    //! ## Shared buffers
+    //!
+    //! With the `bytes` feature, `#[derive(DecodeBytes)]` implements `DecodeBytes` for decoding
+    //! from a shared `bytes::Bytes` buffer, returning `Bytes` fields as reference counted slices
+    //! of the source rather than copying.
 
     pub use encdec_base::decode::*;
 
-    pub use crate::derive::{Decode, DecodeOwned};
+    pub use crate::derive::{Decode, DecodeOwned, DecodeSplit};
+
+    #[cfg(feature = "bytes")]
+    pub use crate::derive::DecodeBytes;
 }
 
 // Re-export macros
@@ -180,6 +308,52 @@ pub mod derive {
     //! `#[encdec(error = "E")]` where `E` is a user error type implementing
     //! `From<encdec::Error>`
     //!
+    //! When using the default [`Error`] type, derived implementations shift error offsets
+    //! by the offset of the failing field so these are reported relative to the outer buffer.
+    //!
+    //! Setting the struct level `#[encdec(context)]` attribute switches derived methods to
+    //! the [`ContextError`] type, which records the struct / field path of a failure
+    //! (ie. `Packet.header.len @ 4: ...`) without allocating. Where combined with
+    //! `#[encdec(error = "E")]`, `E` must implement `From<ContextError>`.
+    //!
+    //! ### Exact decoding
+    //!
+    //! Setting the struct level `#[encdec(exact)]` attribute causes derived decode methods
+    //! to return [`Error::TrailingBytes`] where the object does not consume the whole buffer,
+    //! useful for top-level message types. For other types see
+    //! [`DecodeExt::decode_exact`][crate::DecodeExt::decode_exact].
+    //!
+    //! ### Validation
+    //!
+    //! Validation functions may be specified with `#[encdec(validate = "path::to_fn")]`
+    //! at the struct level, called with `&Self` once the object is decoded, or at the
+    //! field level, called with `&T` once the field is decoded.
+    //! Validation functions return `Result<(), E>` where `E` is converted to the derived
+    //! error type. Setting the struct level `#[encdec(validate_encode)]` attribute also
+    //! calls these functions prior to encoding.
+    //!
+    //! ```
+    //! # use encdec::{Encode, Decode, Error};
+    //! #[derive(Debug, PartialEq, Encode, Decode)]
+    //! #[encdec(validate = "Range::check")]
+    //! struct Range {
+    //!     start: u8,
+    //!     end: u8,
+    //! }
+    //!
+    //! impl Range {
+    //!     fn check(&self) -> Result<(), Error> {
+    //!         match self.start <= self.end {
+    //!             true => Ok(()),
+    //!             false => Err(Error::InvalidValue{ offset: 0 }),
+    //!         }
+    //!     }
+    //! }
+    //!
+    //! assert!(Range::decode(&[1, 2]).is_ok());
+    //! assert_eq!(Range::decode(&[2, 1]), Err(Error::InvalidValue{ offset: 0 }));
+    //! ```
+    //!
     //! ### Encode/Decode methods
     //!
     //! Field encode/decode methods may be overridden using a field level attribute
@@ -190,8 +364,309 @@ pub mod derive {
     //!
     //!
     //! Individual methods may be overridden if required using `#[encdec(enc = "..", enc_len = "..", dec = "..")]` with the same type signatures / constraints as above.
+    //! Where `enc` is provided without `enc_len` the field length is computed by encoding to
+    //! scratch buffers (see [`scratch_len`][crate::encode::scratch_len]), limiting fields to
+    //! [`SINK_SCRATCH`][crate::encode::SINK_SCRATCH] bytes without the `alloc` feature.
+    //!
+    //! Setting the `sink` flag (ie. `#[encdec(with = "M", sink)]` or `#[encdec(enc = "..", sink)]`)
+    //! selects encoders writing to a [`Sink`][crate::encode::Sink] in place of a slice:
+    //! - `fn enc<S: Sink + ?Sized>(&T, &mut S) -> Result<usize, E>`
+    //!
+    //! Without `enc_len` the field length is then computed by counting the encoded bytes
+    //! (see [`count_len`][crate::encode::count_len]), so fields of any length are supported
+    //! without scratch buffers.
+    //!
+    //! ### Length checks
+    //!
+    //! Setting the struct level `#[encdec(check_len)]` attribute causes derived encode methods
+    //! to assert each field's `encode_len` matches the encoded length in debug builds, useful
+    //! for catching mismatched `enc` / `enc_len` overrides. For manual implementations see
+    //! [`EncodeSink::counted_len`][crate::encode::EncodeSink::counted_len].
+    //!
+    //! ### Encoded size bounds
+    //!
+    //! `#[derive(Encode)]` also implements [`MaxEncodedLen`][crate::encode::MaxEncodedLen]
+    //! and [`FixedEncodedLen`][crate::encode::FixedEncodedLen] where all fields are bounded
+    //! (and no `with` / `enc` overrides are used), allowing static buffers to be sized from a type.
+    //! For fixed layouts the struct level `#[encdec(size = N)]` attribute asserts the encoded
+    //! length at compile time.
+    //!
+    //! ```
+    //! # #[cfg(feature = "heapless")]
+    //! # fn main() {
+    //! # use encdec::{Encode, encode::MaxEncodedLen};
+    //! #[derive(Debug, PartialEq, Encode)]
+    //! #[encdec(size = 7)]
+    //! struct Header {
+    //!     kind: u8,
+    //!     seq: u32,
+    //!     crc: u16,
+    //! }
+    //!
+    //! #[derive(Debug, PartialEq, Encode)]
+    //! struct Message {
+    //!     header: Header,
+    //!     data: heapless::Vec<u8, 32>,
+    //! }
+    //!
+    //! // Transmit buffers sized for the largest message
+    //! let mut tx = [0u8; Message::MAX_ENCODED_LEN];
+    //! assert_eq!(tx.len(), 7 + 32);
+    //!
+    //! let m = Message{ header: Header{ kind: 1, seq: 2, crc: 3 }, data: heapless::Vec::new() };
+    //! assert_eq!(m.encode(&mut tx), Ok(7));
+    //! # }
+    //! # #[cfg(not(feature = "heapless"))]
+    //! # fn main() {}
+    //! ```
+    //!
+    //! ### Chunked encoding
+    //!
+    //! `#[derive(EncodeChunked)]` additionally implements [`EncodeChunked`][crate::encode::EncodeChunked],
+    //! allowing large messages to be streamed out through small buffers using
+    //! [`ChunkedEncoder`][crate::encode::ChunkedEncoder] without a full-size intermediate buffer.
+    //!
+    //! ```
+    //! # use encdec::{Encode, encode::{ChunkedEncoder, EncodeChunked}};
+    //! #[derive(Debug, PartialEq, Encode, EncodeChunked)]
+    //! struct Message {
+    //!     id: u16,
+    //!     data: [u32; 64],
+    //! }
+    //!
+    //! let m = Message{ id: 1, data: [0xabcd; 64] };
+    //! let mut e = ChunkedEncoder::new(&m).unwrap();
+    //!
+    //! let mut chunk = [0u8; 16];
+    //! let mut total = 0;
+    //! loop {
+    //!     let n = e.next_chunk(&mut chunk).unwrap();
+    //!     if n == 0 {
+    //!         break;
+    //!     }
+    //!     // write `chunk[..n]` out
+    //!     total += n;
+    //! }
+    //! assert_eq!(total, 2 + 64 * 4);
+    //! ```
+    //!
+    //! Fields using `with` or `enc` overrides are re-encoded via a scratch buffer for each chunk,
+    //! limited to [`CHUNK_SCRATCH`][crate::encode::CHUNK_SCRATCH] bytes without the `alloc` feature.
+    //! Larger overrides should use sink-based encoders with the `sink` flag, which are streamed
+    //! without staging.
+    //!
+    //! ### Sink encoding
+    //!
+    //! `#[derive(EncodeSink)]` additionally implements [`EncodeSink`][crate::encode::EncodeSink],
+    //! streaming fields to a generic [`Sink`][crate::encode::Sink] such as a hasher, checksum
+    //! accumulator or byte [`Counter`][crate::encode::Counter] without an intermediate buffer.
+    //!
+    //! ```
+    //! # use encdec::{Encode, encode::{EncodeSink, FnSink}};
+    //! #[derive(Debug, PartialEq, Encode, EncodeSink)]
+    //! struct Message {
+    //!     id: u16,
+    //!     data: [u8; 4],
+    //! }
+    //!
+    //! // Compute a simple checksum over the encoded message
+    //! let mut sum = 0u8;
+    //! let m = Message{ id: 0x0102, data: [1, 2, 3, 4] };
+    //! m.encode_to(&mut FnSink::new(|d: &[u8]| {
+    //!     sum = d.iter().fold(sum, |a, b| a.wrapping_add(*b))
+    //! })).unwrap();
+    //! assert_eq!(sum, 13);
+    //! ```
+    //!
+    //! ### Vectored encoding
+    //!
+    //! With the `std` feature, `#[derive(EncodeVectored)]` implements
+    //! [`EncodeVectored`][crate::encode::EncodeVectored], encoding headers into a small scratch
+    //! buffer while referencing byte payloads in place, producing
+    //! [`IoSlice`][std::io::IoSlice]s for [`Write::write_vectored`][std::io::Write::write_vectored]
+    //! so large frames may be forwarded without copying.
+    //!
+    //! ```
+    //! # #[cfg(feature = "std")]
+    //! # fn main() {
+    //! # use encdec::{Encode, encode::{EncodeVectored, Vectored}};
+    //! #[derive(Debug, PartialEq, Encode, EncodeVectored)]
+    //! struct Frame<'a> {
+    //!     kind: u8,
+    //!     #[encdec(length_of = "payload")]
+    //!     len: u16,
+    //!     payload: &'a [u8],
+    //! }
+    //!
+    //! let data = [0xab; 1024];
+    //! let f = Frame{ kind: 1, len: 0, payload: &data };
+    //!
+    //! let mut v = Vectored::new();
+    //! f.encode_vectored(&mut v).unwrap();
+    //!
+    //! // Header in scratch, payload referenced directly
+    //! let slices = v.io_slices();
+    //! assert_eq!(slices.len(), 2);
+    //! assert_eq!(&*slices[0], &[1, 0x00, 0x04]);
+    //!
+    //! let mut out = Vec::new();
+    //! assert_eq!(v.write_to(&mut out).unwrap(), 3 + 1024);
+    //! # }
+    //! # #[cfg(not(feature = "std"))]
+    //! # fn main() {}
+    //! ```
+    //!
+    //! ### Borrowed views
+    //!
+    //! Fields of type `&'a [u8; N]` (or `&'a [T; N]` where `T: Pod`) are decoded as zero-copy
+    //! views into the source buffer, as are `&'a [T]` fields with a `#[encdec(length = "..")]`
+    //! byte length attribute. See [`Pod`][crate::decode::Pod] for the requirements on element types,
+    //! with multi-byte primitives borrowed via little-endian wrappers such as
+    //! [`U16Le`][crate::decode::U16Le] (ie. `&'a [U16Le]`).
+
+    pub use encdec_macros::{Decode, DecodeOwned, DecodeSplit, Encode, EncodeChunked, EncodeSink};
+
+    #[cfg(feature = "std")]
+    pub use encdec_macros::EncodeVectored;
+
+    #[cfg(feature = "bytes")]
+    pub use encdec_macros::DecodeBytes;
+
+    #[cfg(feature = "arbitrary")]
+    pub use encdec_macros::Arbitrary;
+}
+
+pub mod bits {
+    //! Bit-level reader and writer primitives for bit-packed formats
+    //!
+    //! ## Example
+    //! ```
+    //! # use encdec::bits::{BitOrder, BitReader, BitWriter};
+    //! let mut buff = [0u8; 2];
+    //!
+    //! // Write a 3-bit flag field followed by an 11-bit length
+    //! let mut w = BitWriter::new(&mut buff, BitOrder::Msb);
+    //! w.write_bits(&0b101u8, 3).unwrap();
+    //! w.write_bits(&0x2aau16, 11).unwrap();
+    //! assert_eq!(w.finish(), 2);
+    //!
+    //! let mut r = BitReader::new(&buff, BitOrder::Msb);
+    //! assert_eq!(r.read_bits::<u8>(3).unwrap(), 0b101);
+    //! assert_eq!(r.read_bits::<u16>(11).unwrap(), 0x2aa);
+    //! ```
+
+    pub use encdec_base::bits::*;
+}
+
+pub mod stuffing {
+    //! Byte-stuffing frame layers (COBS, SLIP and HDLC-style escaping) for serial links
+    //!
+    //! ## Example
+    //! ```
+    //! # #[cfg(feature = "heapless")]
+    //! # fn main() {
+    //! # use encdec::{Encode, Decode, stuffing::{decode_frame, encode_frame, FrameSplitter, Hdlc}};
+    //! #[derive(Debug, PartialEq, Encode, Decode)]
+    //! struct Message<'a> {
+    //!     id: u8,
+    //!     #[encdec(length_of = "data")]
+    //!     len: u8,
+    //!     #[encdec(length = "len")]
+    //!     data: &'a [u8],
+    //! }
+    //!
+    //! // Encode a frame, escaping flag bytes
+    //! let mut buff = [0u8; 32];
+    //! let n = encode_frame::<Hdlc, _>(&Message{ id: 0x7e, len: 0, data: &[1, 2] }, &mut buff).unwrap();
+    //! assert_eq!(&buff[..n], &[0x7e, 0x7d, 0x5e, 0x02, 0x01, 0x02, 0x7e]);
+    //!
+    //! // Split and unstuff frames from a received stream
+    //! let mut s = FrameSplitter::<Hdlc, heapless::Vec<u8, 32>>::new();
+    //! s.push(&buff[..n]);
+    //!
+    //! let mut frame = [0u8; 32];
+    //! let len = s.poll(&mut frame).unwrap().unwrap();
+    //! let (m, _) = Message::decode(&frame[..len]).unwrap();
+    //! assert_eq!(m, Message{ id: 0x7e, len: 2, data: &[1, 2] });
+    //!
+    //! // Or unstuff and decode a complete frame in place
+    //! let m = decode_frame::<Hdlc, Message>(&mut buff[..n]).unwrap();
+    //! assert_eq!(m, Message{ id: 0x7e, len: 2, data: &[1, 2] });
+    //! # }
+    //! # #[cfg(not(feature = "heapless"))]
+    //! # fn main() {}
+    //! ```
+
+    pub use encdec_base::stuffing::*;
+}
+
+#[cfg(feature = "std")]
+pub mod io {
+    //! [`std::io`] adapters for encoding to writers and decoding from readers
+    //!
+    //! ## Example
+    //! ```
+    //! # use encdec::{Encode, DecodeOwned, EncodeExt, DecodeOwnedExt, io::DecodeReader};
+    //! #[derive(Debug, PartialEq, Encode, DecodeOwned)]
+    //! struct Message {
+    //!     id: u8,
+    //!     value: u16,
+    //! }
+    //!
+    //! let mut w = Vec::new();
+    //! Message{ id: 1, value: 2 }.encode_to_writer(&mut w).unwrap();
+    //! Message{ id: 2, value: 3 }.encode_to_writer(&mut w).unwrap();
+    //!
+    //! // Decode a single message from a reader
+    //! let mut r = &w[..];
+    //! let m = Message::decode_from_reader(&mut r).unwrap();
+    //! assert_eq!(m, Message{ id: 1, value: 2 });
+    //!
+    //! // Or decode a stream of messages
+    //! let messages: Result<Vec<Message>, _> = DecodeReader::new(&w[..]).collect();
+    //! assert_eq!(messages.unwrap().len(), 2);
+    //! ```
+
+    pub use encdec_base::io::*;
+}
+
+#[cfg(feature = "embedded-io")]
+pub mod embedded {
+    //! [`embedded_io`] adapters for encoding to writers and decoding from readers
+    //! in `no_std` environments, using caller-provided scratch buffers
+    //!
+    //! ## Example
+    //! ```
+    //! # use encdec::{Encode, DecodeOwned, embedded::{encode_to_writer, decode_from_reader}};
+    //! #[derive(Debug, PartialEq, Encode, DecodeOwned)]
+    //! struct Message {
+    //!     id: u8,
+    //!     value: u16,
+    //! }
+    //!
+    //! let mut scratch = [0u8; 16];
+    //! let mut uart = [0u8; 16];
+    //!
+    //! let m = Message{ id: 1, value: 2 };
+    //! let n = encode_to_writer(&m, &mut &mut uart[..], &mut scratch).unwrap();
+    //!
+    //! let mut r = &uart[..n];
+    //! let d = decode_from_reader::<Message, _>(&mut r, &mut scratch).unwrap();
+    //! assert_eq!(m, d);
+    //! ```
+
+    pub use encdec_base::embedded::*;
+}
+
+#[cfg(feature = "tokio-util")]
+pub mod codec {
+    //! [`tokio_util::codec`] adapter for encodable / decodable types, with pluggable framing
+    //!
+    //! [`EncDecCodec`] supports self-delimiting ([`SelfDelimited`]), fixed size ([`FixedSize`]),
+    //! length-prefixed ([`LengthPrefixed`]) and delimiter-based ([`Delimited`]) framing,
+    //! or custom framing via the [`Framing`] trait.
 
-    pub use encdec_macros::{Decode, DecodeOwned, Encode};
+    pub use encdec_base::codec::*;
 }
 
 // Re-export helpers
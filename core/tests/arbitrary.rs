@@ -0,0 +1,83 @@
+#![cfg(feature = "arbitrary")]
+
+use arbitrary::{Arbitrary, Unstructured};
+use rand::random;
+
+use encdec::{
+    derive::Arbitrary,
+    helpers::{fuzz_roundtrip, test_encode_decode},
+    Decode, Encode,
+};
+
+#[derive(Debug, PartialEq, Encode, Decode, Arbitrary)]
+struct Basic {
+    a: u8,
+    b: u16,
+    c: [u8; 3],
+}
+
+#[derive(Debug, PartialEq, Encode, Decode, Arbitrary)]
+struct Refs<'a> {
+    #[encdec(length_of = "a")]
+    l: u8,
+
+    #[encdec(length = "l")]
+    a: &'a [u8],
+
+    b: u32,
+}
+
+#[derive(Debug, PartialEq, Encode, Decode, Arbitrary)]
+struct Tuple(u8, u16);
+
+fn random_data(n: usize) -> Vec<u8> {
+    (0..n).map(|_| random()).collect()
+}
+
+#[test]
+fn arbitrary_basic() {
+    let mut buff = [0u8; 256];
+
+    for _ in 0..100 {
+        let data = random_data(64);
+        let v = Basic::arbitrary(&mut Unstructured::new(&data)).unwrap();
+        test_encode_decode(&mut buff, v);
+
+        let v = Tuple::arbitrary(&mut Unstructured::new(&data)).unwrap();
+        test_encode_decode(&mut buff, v);
+    }
+}
+
+#[test]
+fn arbitrary_length_of() {
+    let mut buff = [0u8; 1024];
+    let mut count = 0;
+
+    for _ in 0..100 {
+        // Objects exceeding the `u8` length field are rejected
+        let data = random_data(512);
+        let v = match Refs::arbitrary(&mut Unstructured::new(&data)) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+
+        assert_eq!(v.l as usize, v.a.len());
+        test_encode_decode(&mut buff, v);
+        count += 1;
+    }
+
+    assert!(count > 0);
+}
+
+#[test]
+fn fuzz_roundtrip_derived() {
+    let mut buff = [0u8; 1024];
+
+    for i in 0..100 {
+        let data = random_data(i);
+
+        let _ = fuzz_roundtrip::<Basic>(&mut buff, &data);
+        let _ = fuzz_roundtrip::<Refs>(&mut buff, &data);
+        let _ = fuzz_roundtrip::<Tuple>(&mut buff, &data);
+    }
+}
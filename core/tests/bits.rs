@@ -0,0 +1,99 @@
+use rand::random;
+
+use encdec::{
+    bits::{BitOrder, BitReader, BitWriter},
+    helpers::test_encode_decode_bits,
+    Error,
+};
+
+#[test]
+fn encode_decode_bits_unsigned() {
+    let mut buff = [0u8; 16];
+
+    for order in [BitOrder::Msb, BitOrder::Lsb] {
+        test_encode_decode_bits::<u8>(&mut buff, order, 3, random::<u8>() & 0x07);
+        test_encode_decode_bits::<u16>(&mut buff, order, 11, random::<u16>() & 0x7ff);
+        test_encode_decode_bits::<u32>(&mut buff, order, 27, random::<u32>() & 0x7ff_ffff);
+        test_encode_decode_bits::<u64>(&mut buff, order, 64, random());
+    }
+}
+
+#[test]
+fn encode_decode_bits_signed() {
+    let mut buff = [0u8; 16];
+
+    for order in [BitOrder::Msb, BitOrder::Lsb] {
+        test_encode_decode_bits::<i8>(&mut buff, order, 4, -8);
+        test_encode_decode_bits::<i8>(&mut buff, order, 4, 7);
+        test_encode_decode_bits::<i16>(&mut buff, order, 12, -1234);
+        test_encode_decode_bits::<i64>(&mut buff, order, 64, random());
+    }
+}
+
+#[test]
+fn encode_decode_bits_bool() {
+    let mut buff = [0u8; 16];
+
+    test_encode_decode_bits(&mut buff, BitOrder::Msb, 1, true);
+    test_encode_decode_bits(&mut buff, BitOrder::Lsb, 1, false);
+}
+
+#[test]
+fn bits_msb_layout() {
+    let mut buff = [0xffu8; 2];
+
+    let mut w = BitWriter::new(&mut buff, BitOrder::Msb);
+    w.write(0b101, 3).unwrap();
+    w.write(0b0_0000_0001, 9).unwrap();
+    w.align().unwrap();
+
+    assert_eq!(w.finish(), 2);
+    assert_eq!(buff, [0b1010_0000, 0b0001_0000]);
+}
+
+#[test]
+fn bits_lsb_layout() {
+    let mut buff = [0xffu8; 2];
+
+    let mut w = BitWriter::new(&mut buff, BitOrder::Lsb);
+    w.write(0b101, 3).unwrap();
+    w.write(0b1_0000_0001, 9).unwrap();
+
+    assert_eq!(w.finish(), 2);
+    assert_eq!(buff[0], 0b0000_1101);
+    assert_eq!(buff[1] & 0x0f, 0b1000);
+
+    let mut r = BitReader::new(&buff, BitOrder::Lsb);
+    assert_eq!(r.read(3), Ok(0b101));
+    assert_eq!(r.read(9), Ok(0b1_0000_0001));
+}
+
+#[test]
+fn bits_overflow() {
+    let mut buff = [0u8; 1];
+
+    // Value exceeds field width
+    let mut w = BitWriter::new(&mut buff, BitOrder::Msb);
+    assert_eq!(w.write_bits(&0x10u8, 4), Err(Error::Overflow { offset: 0 }));
+    assert_eq!(w.write_bits(&-9i8, 4), Err(Error::Overflow { offset: 0 }));
+
+    // Field exceeds buffer
+    w.write_bits(&0u8, 4).unwrap();
+    assert_eq!(
+        w.write_bits(&0u16, 9),
+        Err(Error::BufferTooSmall {
+            needed: 2,
+            available: 1,
+            offset: 0
+        })
+    );
+
+    let mut r = BitReader::new(&buff, BitOrder::Msb);
+    assert_eq!(
+        r.read_bits::<u16>(9),
+        Err(Error::Incomplete {
+            needed: 1,
+            offset: 0
+        })
+    );
+}
@@ -0,0 +1,142 @@
+#![cfg(feature = "bytes")]
+
+use bytes::{BufMut, Bytes, BytesMut};
+use rand::random;
+
+use encdec::{decode::DecodeBytes, Decode, DecodeOwned, Encode, EncodeExt, Error};
+
+#[derive(Debug, PartialEq, Encode, DecodeOwned)]
+struct Header {
+    a: u8,
+    b: u16,
+}
+
+#[test]
+fn encode_buf_bytes_mut() {
+    let h = Header {
+        a: random(),
+        b: random(),
+    };
+
+    let mut buf = BytesMut::new();
+    buf.put_u8(0xaa);
+    buf.reserve(h.encode_len().unwrap());
+
+    let n = h.encode_buf(&mut buf).unwrap();
+    assert_eq!(n, 3);
+    assert_eq!(buf.len(), 4);
+
+    let (h1, _) = Header::decode(&buf[1..]).unwrap();
+    assert_eq!(h, h1);
+}
+
+#[test]
+fn encode_buf_reserves() {
+    let h = Header {
+        a: random(),
+        b: random(),
+    };
+
+    // Space is reserved where the buffer has no spare capacity
+    let mut buf = BytesMut::new();
+    let n = h.encode_buf(&mut buf).unwrap();
+
+    assert_eq!(&buf[..], &h.encode_vec().unwrap().0[..n]);
+}
+
+#[test]
+fn encode_buf_generic() {
+    let h = Header { a: 1, b: 0x0302 };
+
+    // Growable buffers
+    let mut v = vec![0xaa];
+    assert_eq!(h.encode_buf(&mut v), Ok(3));
+    assert_eq!(&v[..], &[0xaa, 1, 2, 3]);
+
+    // Limited buffers reject objects exceeding the limit
+    let mut l = BytesMut::new().limit(2);
+    assert_eq!(
+        h.encode_buf(&mut l),
+        Err(Error::BufferTooSmall {
+            needed: 3,
+            available: 2,
+            offset: 0
+        })
+    );
+    assert!(l.get_ref().is_empty());
+
+    // Chained buffers are written across chunks
+    let (mut a, mut b) = ([0u8; 2], [0u8; 2]);
+    let mut c = (&mut a[..]).chain_mut(&mut b[..]);
+    assert_eq!(h.encode_buf(&mut c), Ok(3));
+    assert_eq!((a, b), ([1, 2], [3, 0]));
+}
+
+#[derive(Debug, PartialEq, Encode)]
+struct Failing {
+    a: u8,
+    #[encdec(enc = "fail", enc_len = "fail_len")]
+    b: u8,
+}
+
+fn fail(_v: &u8, _buff: &mut [u8]) -> Result<usize, Error> {
+    Err(Error::InvalidValue { offset: 0 })
+}
+
+fn fail_len(_v: &u8) -> Result<usize, Error> {
+    Ok(1)
+}
+
+#[test]
+fn encode_buf_error() {
+    let mut buf = BytesMut::new();
+    buf.put_u8(0xaa);
+
+    // Failed encodes leave existing data unchanged
+    let f = Failing { a: 1, b: 2 };
+    assert_eq!(
+        f.encode_buf(&mut buf),
+        Err(Error::InvalidValue { offset: 1 })
+    );
+    assert_eq!(&buf[..], &[0xaa]);
+}
+
+#[derive(Debug, PartialEq, Encode, DecodeBytes)]
+struct Frame {
+    header: Header,
+    #[encdec(length_of = "payload")]
+    len: u8,
+    #[encdec(length = "len")]
+    payload: Bytes,
+    trailer: u8,
+}
+
+#[test]
+fn decode_bytes_zero_copy() {
+    let buff = Bytes::from_static(&[0x01, 0x02, 0x03, 0x02, 0xaa, 0xbb, 0xcc]);
+
+    let (f, n) = Frame::decode_bytes(&buff).unwrap();
+    assert_eq!(n, 7);
+    assert_eq!(f.header, Header { a: 1, b: 0x0302 });
+    assert_eq!(&f.payload[..], &[0xaa, 0xbb]);
+    assert_eq!(f.trailer, 0xcc);
+
+    // Payload shares the source buffer
+    assert_eq!(f.payload.as_ptr(), buff[4..].as_ptr());
+
+    // Round trip via the derived encoder
+    assert_eq!(&f.encode_vec().unwrap().0[..], &buff[..]);
+}
+
+#[test]
+fn decode_bytes_errors() {
+    // Truncated payloads report the field offset
+    let buff = Bytes::from_static(&[0x01, 0x02, 0x03, 0x04, 0xaa, 0xbb]);
+    assert_eq!(
+        Frame::decode_bytes(&buff),
+        Err(Error::Incomplete {
+            needed: 2,
+            offset: 4
+        })
+    );
+}
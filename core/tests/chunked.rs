@@ -0,0 +1,232 @@
+//! Tests for resumable chunked encoding with [`ChunkedEncoder`]
+
+#![cfg(feature = "alloc")]
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use encdec::{
+    encode::{ChunkedEncoder, EncodeChunked, EncodeSink, Sink, CHUNK_SCRATCH},
+    Encode, EncodeExt, Error,
+};
+
+#[derive(Debug, PartialEq, Encode, EncodeChunked)]
+struct Header {
+    kind: u8,
+    seq: u32,
+}
+
+#[derive(Debug, PartialEq, Encode, EncodeChunked)]
+struct Message<'a> {
+    header: Header,
+    #[encdec(length_of = "payload")]
+    len: u16,
+    payload: Vec<u32>,
+    #[encdec(with = "be_u16")]
+    crc: u16,
+    name: &'a str,
+    #[encdec(enc = "enc_pad", enc_len = "pad_len")]
+    pad: [u8; 80],
+    #[encdec(enc = "enc_trailer", sink)]
+    trailer: [u8; 100],
+}
+
+/// Sink encoder larger than the scratch buffer, written in parts
+fn enc_trailer<S: Sink + ?Sized>(v: &[u8; 100], sink: &mut S) -> Result<usize, Error> {
+    for c in v.chunks(7) {
+        sink.write_bytes(c)?;
+    }
+    Ok(v.len())
+}
+
+/// Slice encoder larger than [`CHUNK_SCRATCH`], staged on the heap
+fn enc_pad(v: &[u8; 80], buff: &mut [u8]) -> Result<usize, Error> {
+    v.encode(buff)
+}
+
+fn pad_len(v: &[u8; 80]) -> Result<usize, Error> {
+    Ok(v.len())
+}
+
+mod be_u16 {
+    use encdec::Error;
+
+    pub fn enc(v: &u16, buff: &mut [u8]) -> Result<usize, Error> {
+        if buff.len() < 2 {
+            return Err(Error::BufferTooSmall {
+                needed: 2,
+                available: buff.len(),
+                offset: 0,
+            });
+        }
+        buff[..2].copy_from_slice(&v.to_be_bytes());
+        Ok(2)
+    }
+
+    pub fn enc_len(_v: &u16) -> Result<usize, Error> {
+        Ok(2)
+    }
+}
+
+fn message() -> Message<'static> {
+    Message {
+        header: Header {
+            kind: 0x11,
+            seq: 0xaabbccdd,
+        },
+        len: 0,
+        payload: (0..200).collect(),
+        crc: 0x1234,
+        name: "chunked message",
+        pad: [0x5a; 80],
+        trailer: core::array::from_fn(|i| i as u8),
+    }
+}
+
+fn encode_chunked<T: EncodeChunked>(v: &T, size: usize) -> Vec<u8> {
+    let mut e = ChunkedEncoder::new(v).unwrap();
+    let mut chunk = vec![0u8; size];
+    let mut out = vec![];
+
+    loop {
+        let n = e.next_chunk(&mut chunk).unwrap();
+        if n == 0 {
+            break;
+        }
+        assert!(n <= size);
+        out.extend_from_slice(&chunk[..n]);
+        assert_eq!(e.position(), out.len());
+    }
+
+    assert!(e.is_complete());
+    assert_eq!(e.remaining(), 0);
+
+    out
+}
+
+#[test]
+fn chunked_matches_encode() {
+    let m = message();
+    let (full, _) = m.encode_vec().unwrap();
+
+    for size in [1, 3, 7, 64, 1024] {
+        assert_eq!(encode_chunked(&m, size), full, "chunk size {size}");
+    }
+}
+
+#[test]
+fn chunked_encode_range() {
+    let m = message();
+    let (full, _) = m.encode_vec().unwrap();
+
+    // Windows starting mid-field resume at the correct byte
+    let mut buff = [0u8; 5];
+    for offset in [0, 1, 4, 6, 9, full.len() - 20, full.len() - 3] {
+        let n = m.encode_range(offset, &mut buff).unwrap();
+        let expected = &full[offset..][..n];
+        assert_eq!(&buff[..n], expected, "offset {offset}");
+        assert_eq!(n, (full.len() - offset).min(buff.len()));
+    }
+
+    // Nothing is written past the end of the object
+    assert_eq!(m.encode_range(full.len(), &mut buff), Ok(0));
+}
+
+#[test]
+fn chunked_errors() {
+    let mut m = message();
+    m.payload = vec![0; 0x10000];
+
+    // Length field overflow is reported when encoding the affected window
+    let mut e = ChunkedEncoder::new(&m).unwrap();
+    let mut chunk = [0u8; 64];
+    assert_eq!(e.next_chunk(&mut chunk), Err(Error::Overflow { offset: 5 }));
+
+    // Empty buffers make no progress without error
+    let m = message();
+    let mut e = ChunkedEncoder::new(&m).unwrap();
+    assert_eq!(e.next_chunk(&mut []), Ok(0));
+    assert_eq!(e.position(), 0);
+}
+
+#[test]
+fn chunked_slices() {
+    let bytes: Vec<u8> = (0..=255).cycle().take(1000).collect();
+    let words: Vec<u16> = (0..500).collect();
+
+    for size in [1, 3, 64] {
+        assert_eq!(encode_chunked(&bytes, size), bytes);
+        assert_eq!(encode_chunked(&words, size), words.encode_vec().unwrap().0);
+    }
+
+    // Windows skip directly to the element containing the offset
+    let mut buff = [0u8; 3];
+    assert_eq!(words.encode_range(7, &mut buff), Ok(3));
+    assert_eq!(buff, [0x00, 0x04, 0x00]);
+    assert_eq!(bytes.as_slice().encode_range(999, &mut buff), Ok(1));
+    assert_eq!(buff[0], bytes[999]);
+    assert_eq!(words.encode_range(1000, &mut buff), Ok(0));
+}
+
+/// Object failing to write the encoded length it reports
+#[derive(Debug)]
+struct Stalled;
+
+impl Encode for Stalled {
+    type Error = Error;
+
+    fn encode_len(&self) -> Result<usize, Self::Error> {
+        Ok(CHUNK_SCRATCH)
+    }
+
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        buff[..CHUNK_SCRATCH].fill(0);
+        Ok(CHUNK_SCRATCH)
+    }
+}
+
+impl EncodeChunked for Stalled {
+    fn encode_range(&self, offset: usize, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        Ok(buff.len().min(8usize.saturating_sub(offset)))
+    }
+}
+
+#[test]
+fn chunked_no_progress() {
+    let mut e = ChunkedEncoder::new(&Stalled).unwrap();
+    let mut chunk = [0u8; 4];
+
+    assert_eq!(e.next_chunk(&mut chunk), Ok(4));
+    assert_eq!(e.next_chunk(&mut chunk), Ok(4));
+    assert_eq!(
+        e.next_chunk(&mut chunk),
+        Err(Error::InvalidValue { offset: 8 })
+    );
+}
+
+static COUNTED_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, PartialEq, Encode, EncodeChunked)]
+struct Counted {
+    a: u16,
+    #[encdec(enc = "enc_counted", sink)]
+    b: u32,
+}
+
+/// Sink encoder counting calls, to check fields past the window are skipped
+fn enc_counted<S: Sink + ?Sized>(v: &u32, sink: &mut S) -> Result<usize, Error> {
+    COUNTED_CALLS.fetch_add(1, Ordering::Relaxed);
+    v.encode_to(sink)
+}
+
+#[test]
+fn chunked_stops_past_window() {
+    let v = Counted {
+        a: 0x0102,
+        b: 0x03040506,
+    };
+    let mut chunk = [0u8; 2];
+
+    assert_eq!(v.encode_range(0, &mut chunk), Ok(2));
+    assert_eq!(chunk, [0x02, 0x01]);
+    assert_eq!(COUNTED_CALLS.load(Ordering::Relaxed), 0);
+}
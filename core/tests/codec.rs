@@ -0,0 +1,142 @@
+#![cfg(feature = "tokio-util")]
+
+use bytes::BytesMut;
+use futures::{SinkExt, StreamExt};
+use tokio_util::codec::{Decoder, Encoder, FramedRead, FramedWrite};
+
+use encdec::{
+    codec::{Delimited, EncDecCodec, FixedSize, LengthPrefixed},
+    io::IoError,
+    DecodeOwned, Encode, Error,
+};
+
+#[derive(Clone, Debug, PartialEq, Encode, DecodeOwned)]
+struct Message {
+    id: u8,
+    value: u32,
+}
+
+fn messages() -> Vec<Message> {
+    (1..=4)
+        .map(|i| Message {
+            id: i,
+            value: i as u32 * 1000,
+        })
+        .collect()
+}
+
+#[test]
+fn codec_need_more() {
+    let mut codec = EncDecCodec::<Message>::new();
+
+    let mut buff = BytesMut::new();
+    codec.encode(messages()[0].clone(), &mut buff).unwrap();
+    let mut rest = buff.split_off(3);
+
+    // Partial messages are not errors
+    assert_eq!(codec.decode(&mut buff).unwrap(), None);
+    assert_eq!(buff.len(), 3);
+
+    buff.unsplit(rest.split());
+    assert_eq!(
+        codec.decode(&mut buff).unwrap(),
+        Some(messages()[0].clone())
+    );
+    assert!(buff.is_empty());
+}
+
+#[test]
+fn codec_malformed() {
+    let mut codec = EncDecCodec::<Message, _>::with_framing(FixedSize::<5>);
+    let mut buff = BytesMut::from(&[0u8; 4][..]);
+    assert_eq!(codec.decode(&mut buff).unwrap(), None);
+
+    // Length-prefixed frames shorter than the object
+    let mut codec = EncDecCodec::<Message, _>::with_framing(LengthPrefixed::<u8>::new());
+    let mut buff = BytesMut::from(&[2u8, 0x01][..]);
+    assert_eq!(codec.decode(&mut buff).unwrap(), None);
+
+    buff.extend_from_slice(&[0x02]);
+    assert!(matches!(
+        codec.decode(&mut buff),
+        Err(IoError::Codec(Error::Incomplete { .. }))
+    ));
+    assert!(buff.is_empty());
+
+    // Delimited frames with trailing data
+    let mut codec = EncDecCodec::<Message, _>::with_framing(Delimited::new(0xff));
+    let mut buff = BytesMut::from(&[0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff][..]);
+    assert!(matches!(
+        codec.decode(&mut buff),
+        Err(IoError::Codec(Error::TrailingBytes { .. }))
+    ));
+    assert!(buff.is_empty());
+
+    // Delimited encode rejects objects containing the delimiter
+    let mut buff = BytesMut::new();
+    let m = Message { id: 0xff, value: 0 };
+    assert!(codec.encode(m, &mut buff).is_err());
+    assert!(buff.is_empty());
+
+    // Fixed size encode rejects objects not filling the frame
+    let mut codec = EncDecCodec::<Message, _>::with_framing(FixedSize::<4>);
+    assert!(matches!(
+        codec.encode(messages()[0].clone(), &mut buff),
+        Err(IoError::Codec(Error::BufferTooSmall {
+            needed: 5,
+            available: 4,
+            offset: 0
+        }))
+    ));
+
+    let mut codec = EncDecCodec::<Message, _>::with_framing(FixedSize::<6>);
+    assert!(matches!(
+        codec.encode(messages()[0].clone(), &mut buff),
+        Err(IoError::Codec(Error::InvalidValue { offset: 0 }))
+    ));
+    assert!(buff.is_empty());
+}
+
+async fn round_trip<F>(framing: F)
+where
+    F: encdec::codec::Framing + Clone + Send + 'static,
+{
+    let (a, b) = tokio::io::duplex(8);
+
+    let mut w = FramedWrite::new(a, EncDecCodec::<Message, _>::with_framing(framing.clone()));
+    let mut r = FramedRead::new(b, EncDecCodec::<Message, _>::with_framing(framing));
+
+    let tx = tokio::spawn(async move {
+        for m in messages() {
+            w.send(m).await.unwrap();
+        }
+    });
+
+    let mut received = vec![];
+    while let Some(m) = r.next().await {
+        received.push(m.unwrap());
+    }
+
+    tx.await.unwrap();
+    assert_eq!(received, messages());
+}
+
+#[tokio::test]
+async fn codec_duplex_self_delimited() {
+    round_trip(encdec::codec::SelfDelimited).await;
+}
+
+#[tokio::test]
+async fn codec_duplex_fixed() {
+    round_trip(FixedSize::<5>).await;
+}
+
+#[tokio::test]
+async fn codec_duplex_length_prefixed() {
+    round_trip(LengthPrefixed::<u16>::new()).await;
+}
+
+#[tokio::test]
+async fn codec_duplex_delimited() {
+    round_trip(Delimited::new(0xaa)).await;
+}
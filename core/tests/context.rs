@@ -0,0 +1,106 @@
+use encdec::{helpers::test_encode_decode, Decode, Encode, Error};
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+#[encdec(context)]
+struct Header {
+    kind: u8,
+    len: u16,
+}
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+#[encdec(context)]
+struct Packet {
+    seq: u8,
+    header: Header,
+    crc: u32,
+}
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+#[encdec(context)]
+struct Wrapper(u8, Packet);
+
+#[cfg(feature = "alloc")]
+#[derive(Debug, PartialEq, Encode)]
+#[encdec(context)]
+struct Named {
+    #[encdec(length_of = "data")]
+    len: u8,
+    data: Vec<u8>,
+}
+
+#[test]
+fn context_derive() {
+    let mut buff = [0u8; 256];
+
+    test_encode_decode(
+        &mut buff,
+        Packet {
+            seq: 1,
+            header: Header { kind: 2, len: 3 },
+            crc: 4,
+        },
+    );
+}
+
+#[test]
+fn context_decode_path() {
+    let buff = [0x01, 0x02, 0x03];
+
+    let e = Packet::decode(&buff).unwrap_err();
+
+    assert_eq!(e.object(), Some("Packet"));
+    assert_eq!(e.path(), &["header", "len"]);
+    assert_eq!(e.offset(), Some(2));
+    assert_eq!(
+        e.error(),
+        Error::Incomplete {
+            needed: 1,
+            offset: 2
+        }
+    );
+    assert_eq!(
+        e.to_string(),
+        "Packet.header.len @ 2: incomplete object at offset 2 (needed 1 more bytes)"
+    );
+}
+
+#[test]
+fn context_tuple_path() {
+    let buff = [0x00, 0x01, 0x02, 0x03, 0x04];
+
+    let e = Wrapper::decode(&buff).unwrap_err();
+
+    assert_eq!(e.object(), Some("Wrapper"));
+    assert_eq!(e.path(), &["1", "crc"]);
+    assert_eq!(e.offset(), Some(5));
+}
+
+#[test]
+fn context_encode_path() {
+    let mut buff = [0u8; 4];
+
+    let p = Packet {
+        seq: 1,
+        header: Header { kind: 2, len: 3 },
+        crc: 4,
+    };
+
+    // Undersized buffers are rejected prior to writing any fields
+    let e = p.encode(&mut buff).unwrap_err();
+    assert_eq!(
+        e.to_string(),
+        "Packet @ 0: buffer too small at offset 0 (needed 8 bytes, available 4)"
+    );
+
+    #[cfg(feature = "alloc")]
+    {
+        let n = Named {
+            len: 0,
+            data: vec![0u8; 300],
+        };
+
+        let mut buff = [0u8; 512];
+        let e = n.encode(&mut buff).unwrap_err();
+        assert_eq!(e.to_string(), "Named.len @ 0: value overflow at offset 0");
+    }
+}
@@ -0,0 +1,153 @@
+//! Tests for hand-written implementations using [`EncodeCursor`] / [`DecodeCursor`]
+
+use encdec::{decode::DecodeCursor, encode::EncodeCursor, Decode, DecodeOwned, Encode, Error};
+
+/// Manual codec with a reserved byte and length-tagged string
+#[derive(Debug, PartialEq)]
+struct Record<'a> {
+    kind: u8,
+    name: &'a str,
+    value: u32,
+}
+
+impl<'a> Encode for Record<'a> {
+    type Error = Error;
+
+    fn encode_len(&self) -> Result<usize, Self::Error> {
+        Ok(1 + 1 + 1 + self.name.len() + 4)
+    }
+
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        let mut c = EncodeCursor::new(buff);
+
+        c.write(&self.kind)?;
+        c.skip(1)?;
+        c.write(&(self.name.len() as u8))?;
+        c.write(&self.name)?;
+        c.write(&self.value)
+            .map_err(|e| e.offset_by(c.position()))?;
+
+        Ok(c.position())
+    }
+}
+
+impl<'a> Decode<'a> for Record<'a> {
+    type Output = Record<'a>;
+    type Error = Error;
+
+    fn decode(buff: &'a [u8]) -> Result<(Self::Output, usize), Self::Error> {
+        let mut c = DecodeCursor::new(buff);
+
+        let kind = c.read::<u8>()?;
+        c.skip(1)?;
+        let len = c.read::<u8>()?;
+        let name = c.read_len::<&str>(len as usize)?;
+        let value = c.read::<u32>().map_err(|e| e.offset_by(c.position()))?;
+
+        Ok((Self { kind, name, value }, c.position()))
+    }
+}
+
+#[derive(Debug, PartialEq, Encode, DecodeOwned)]
+struct Pair {
+    a: u16,
+    b: u8,
+}
+
+#[test]
+fn cursor_round_trip() {
+    let r = Record {
+        kind: 7,
+        name: "abc",
+        value: 0x01020304,
+    };
+
+    let mut buff = [0xffu8; 16];
+    let n = r.encode(&mut buff).unwrap();
+    assert_eq!(
+        &buff[..n],
+        &[7, 0xff, 3, b'a', b'b', b'c', 0x04, 0x03, 0x02, 0x01]
+    );
+    assert_eq!(n, r.encode_len().unwrap());
+
+    assert_eq!(Record::decode(&buff[..n]), Ok((r, n)));
+}
+
+#[test]
+fn cursor_errors() {
+    let r = Record {
+        kind: 7,
+        name: "abc",
+        value: 0x01020304,
+    };
+
+    // Undersized buffers report the offset of the failing write
+    let mut buff = [0u8; 8];
+    assert_eq!(
+        r.encode(&mut buff),
+        Err(Error::BufferTooSmall {
+            needed: 4,
+            available: 2,
+            offset: 6
+        })
+    );
+
+    // Truncated input reports the offset of the failing read
+    let data = [7, 0, 3, b'a', b'b', b'c', 0x04];
+    assert_eq!(
+        Record::decode(&data),
+        Err(Error::Incomplete {
+            needed: 3,
+            offset: 6
+        })
+    );
+
+    // Skipping past the end of the buffer
+    let mut c = DecodeCursor::new(&data);
+    assert_eq!(
+        c.skip(8),
+        Err(Error::Incomplete {
+            needed: 1,
+            offset: 0
+        })
+    );
+    assert_eq!(c.position(), 0);
+
+    let mut c = EncodeCursor::new(&mut buff);
+    c.skip(6).unwrap();
+    assert_eq!(
+        c.skip(3),
+        Err(Error::BufferTooSmall {
+            needed: 3,
+            available: 2,
+            offset: 6
+        })
+    );
+}
+
+#[test]
+fn cursor_peek() {
+    let data = [0x01, 0x02, 0x03, 0x04];
+    let mut c = DecodeCursor::new(&data);
+
+    // Peeking does not advance
+    assert_eq!(c.peek::<u16>(), Ok(0x0201));
+    assert_eq!(c.peek::<u8>(), Ok(0x01));
+    assert_eq!(c.position(), 0);
+
+    // Owned and derived types may be read directly
+    assert_eq!(c.read::<Pair>(), Ok(Pair { a: 0x0201, b: 0x03 }));
+    assert_eq!(c.remaining(), 1);
+    assert_eq!(c.rest(), &[0x04]);
+    assert!(!c.is_empty());
+
+    c.skip(1).unwrap();
+    assert!(c.is_empty());
+    assert_eq!(
+        c.peek::<u8>(),
+        Err(Error::Incomplete {
+            needed: 1,
+            offset: 0
+        })
+    );
+}
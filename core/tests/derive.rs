@@ -1,9 +1,12 @@
-use core::fmt::Debug;
+use core::{cell::Cell, fmt::Debug, marker::PhantomData};
 
 use encdec_base::EncDecOwned;
 use rand::random;
 
-use encdec::{helpers::test_encode_decode, Decode, Encode, Error};
+use encdec::{
+    decode::DecodeLimits, encode::Sink, helpers::test_encode_decode, Decode, DecodeOwned, Encode,
+    EncodeExt, Error,
+};
 
 #[derive(Debug, PartialEq, Encode, Decode)]
 struct Basic {
@@ -47,6 +50,38 @@ fn basic_layout() {
     assert_eq!(&buff[7..][..8], &t.d.to_le_bytes());
 }
 
+#[test]
+fn basic_error_offsets() {
+    let t = Basic {
+        a: random(),
+        b: random(),
+        c: random(),
+        d: random(),
+    };
+    let mut buff = [0u8; 256];
+
+    // Output buffer ends within `c`, rejected prior to writing any fields
+    assert_eq!(
+        t.encode(&mut buff[..5]),
+        Err(Error::BufferTooSmall {
+            needed: 15,
+            available: 5,
+            offset: 0
+        })
+    );
+    assert_eq!(&buff[..5], &[0u8; 5]);
+
+    // Input buffer ends within `d`
+    let n = t.encode(&mut buff).unwrap();
+    assert_eq!(
+        Basic::decode(&buff[..n - 2]),
+        Err(Error::Incomplete {
+            needed: 2,
+            offset: 7
+        })
+    );
+}
+
 #[derive(Debug, PartialEq, Encode, encdec::DecodeOwned)]
 struct BasicOwned {
     a: u8,
@@ -99,6 +134,221 @@ fn tuple_derive() {
     test_encode_decode(&mut buff, Tuple(random(), random()));
 }
 
+#[derive(Debug, PartialEq, Encode, Decode)]
+struct Unit;
+
+#[test]
+fn unit_derive() {
+    let mut buff = [0u8; 256];
+
+    test_encode_decode(&mut buff, Unit);
+    assert_eq!(Unit.encode_len().unwrap(), 0);
+}
+
+#[derive(Debug, PartialEq, Encode)]
+struct Failing {
+    a: u16,
+    #[encdec(enc = "fail_enc", enc_len = "fail_enc_len")]
+    b: u8,
+    c: u8,
+}
+
+fn fail_enc(_v: &u8, _buff: &mut [u8]) -> Result<usize, Error> {
+    Err(Error::InvalidValue { offset: 0 })
+}
+
+fn fail_enc_len(_v: &u8) -> Result<usize, Error> {
+    Ok(1)
+}
+
+#[test]
+fn try_encode_partial() {
+    let t = Failing {
+        a: 0x0201,
+        b: 3,
+        c: 4,
+    };
+    let mut buff = [0xffu8; 8];
+
+    // Undersized buffers are rejected without writing
+    let e = t.try_encode(&mut buff[..3]).unwrap_err();
+    assert_eq!(e.max_written(), 0);
+    assert_eq!(
+        e.into_error(),
+        Error::BufferTooSmall {
+            needed: 4,
+            available: 3,
+            offset: 0
+        }
+    );
+    assert_eq!(buff, [0xff; 8]);
+
+    // Failures part way through report the extent of written data
+    let e = t.try_encode(&mut buff).unwrap_err();
+    assert_eq!(e.error(), &Error::InvalidValue { offset: 2 });
+    assert_eq!(e.max_written(), 4);
+    assert_eq!(&buff[..2], &[0x01, 0x02]);
+    assert_eq!(&buff[4..], &[0xff; 4]);
+}
+
+/// Leaf type counting length computations
+#[derive(Debug, Default)]
+struct CountedLen(Cell<usize>);
+
+impl Encode for CountedLen {
+    type Error = Error;
+
+    fn encode_len(&self) -> Result<usize, Error> {
+        self.0.set(self.0.get() + 1);
+        Ok(1)
+    }
+
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, Error> {
+        self.encode_len()?;
+        self.encode_nested(buff)
+    }
+
+    fn encode_nested(&self, buff: &mut [u8]) -> Result<usize, Error> {
+        *buff.first_mut().ok_or(Error::Length)? = 0xaa;
+        Ok(1)
+    }
+}
+
+#[derive(Debug, Default, Encode)]
+struct NestedInner {
+    a: u8,
+    c: [CountedLen; 2],
+}
+
+#[derive(Debug, Default, Encode)]
+struct NestedOuter {
+    inner: NestedInner,
+    b: u16,
+}
+
+#[test]
+fn encode_checks_length_once() {
+    let v = NestedOuter::default();
+    let mut buff = [0u8; 8];
+
+    // Only the outermost object computes the encoded length
+    assert_eq!(v.encode(&mut buff), Ok(5));
+    assert_eq!(&buff[..5], &[0, 0xaa, 0xaa, 0, 0]);
+    assert_eq!(v.inner.c[0].0.get(), 1);
+    assert_eq!(v.inner.c[1].0.get(), 1);
+}
+
+#[cfg(all(feature = "alloc", feature = "heapless"))]
+#[test]
+fn encode_containers() {
+    let b = Basic {
+        a: 1,
+        b: 0x0302,
+        c: 0x07060504,
+        d: 0x0f0e0d0c0b0a0908,
+    };
+    let (full, n) = b.encode_vec().unwrap();
+
+    // Fixed capacity containers
+    let h = b.encode_heapless::<32>().unwrap();
+    assert_eq!(&h[..], &full[..]);
+
+    #[cfg(feature = "arrayvec")]
+    {
+        let a = b.encode_arrayvec::<15>().unwrap();
+        assert_eq!(&a[..], &full[..]);
+    }
+
+    assert_eq!(
+        b.encode_heapless::<8>(),
+        Err(Error::BufferTooSmall {
+            needed: n,
+            available: 8,
+            offset: 0
+        })
+    );
+
+    // Appending retains existing data
+    let mut v = vec![0xaa, 0xbb];
+    assert_eq!(b.encode_append(&mut v), Ok(n));
+    assert_eq!(&v[..2], &[0xaa, 0xbb]);
+    assert_eq!(&v[2..], &full[..]);
+
+    // Failed appends leave existing data untouched
+    let t = Failing {
+        a: 0x0201,
+        b: 3,
+        c: 4,
+    };
+    assert_eq!(
+        t.encode_append(&mut v),
+        Err(Error::InvalidValue { offset: 2 })
+    );
+    assert_eq!(v.len(), 2 + n);
+
+    // Extendable containers
+    let mut d = std::collections::VecDeque::from([0xcc]);
+    assert_eq!(b.encode_into(&mut d), Ok(n));
+    assert_eq!(d.iter().skip(1).copied().collect::<Vec<_>>(), full);
+
+    let mut e = Vec::new();
+    assert!(t.encode_into(&mut e).is_err());
+    assert!(e.is_empty());
+}
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+#[encdec(exact)]
+struct Exact {
+    a: u8,
+    b: u16,
+}
+
+#[test]
+fn exact_derive() {
+    let buff = [0x01, 0x02, 0x03, 0xff, 0xff];
+
+    assert_eq!(
+        Exact::decode(&buff[..3]),
+        Ok((Exact { a: 0x01, b: 0x0302 }, 3))
+    );
+    assert_eq!(
+        Exact::decode(&buff),
+        Err(Error::TrailingBytes {
+            remaining: 2,
+            offset: 3
+        })
+    );
+
+    // Trailing byte errors leave limits unchanged for reuse
+    let mut limits = DecodeLimits::new().with_max_depth(1);
+    assert!(Exact::decode_limited(&buff, &mut limits).is_err());
+    assert_eq!(limits.depth(), 0);
+    assert!(Exact::decode_limited(&buff[..3], &mut limits).is_ok());
+}
+
+#[derive(Debug, PartialEq)]
+struct Marker;
+
+/// Typestate style objects with [`PhantomData`] markers
+#[derive(Debug, PartialEq, Encode, encdec::DecodeOwned)]
+struct WithMarker<T> {
+    a: u8,
+    _t: PhantomData<T>,
+}
+
+#[test]
+fn marker_derive() {
+    let mut buff = [0u8; 256];
+
+    test_encode_decode(
+        &mut buff,
+        WithMarker::<Marker> {
+            a: random(),
+            _t: PhantomData,
+        },
+    );
+}
+
 /// EXPERIMENTAL References with length descriptors
 ///
 /// perhaps better to have a "delimited" mode? support for headers?
@@ -142,6 +392,52 @@ fn ref_encode_len() {
     assert_eq!(encoded_len, decoded_len);
 }
 
+/// Zero-copy views into borrowed arrays and [`Pod`][encdec::decode::Pod] slices
+#[derive(Debug, PartialEq, Encode, Decode)]
+struct Views<'a> {
+    a: &'a [u8; 4],
+
+    #[encdec(length_of = "b")]
+    l: u8,
+
+    #[encdec(length = "l")]
+    b: &'a [[u8; 2]],
+}
+
+#[test]
+fn view_derive() {
+    let mut buff = [0u8; 256];
+
+    test_encode_decode(
+        &mut buff,
+        Views {
+            a: &[random(), random(), random(), random()],
+            l: 4,
+            b: &[[random(), random()], [random(), random()]],
+        },
+    );
+}
+
+#[test]
+fn view_zero_copy() {
+    let buff = [1, 2, 3, 4, 4, 5, 6, 7, 8];
+
+    let (v, n) = Views::decode(&buff).unwrap();
+    assert_eq!(n, buff.len());
+
+    assert_eq!(v.a.as_ptr(), buff[0..].as_ptr());
+    assert_eq!(v.b.as_ptr() as *const u8, buff[5..].as_ptr());
+    assert_eq!(v.b, &[[5, 6], [7, 8]]);
+}
+
+#[test]
+fn view_length_mismatch() {
+    // Tagged length not a multiple of the element size
+    let buff = [1, 2, 3, 4, 3, 5, 6, 7];
+
+    assert_eq!(Views::decode(&buff), Err(Error::InvalidValue { offset: 5 }));
+}
+
 /// Override encode and decode functions via macro
 #[derive(Debug, PartialEq, Encode, Decode)]
 struct Overrides {
@@ -182,6 +478,117 @@ fn override_enc_dec_with() {
     assert_eq!(&buff[1..][..8], &a.to_be_bytes());
 }
 
+/// Sink encode overrides without length functions, computed by counting
+#[derive(Debug, PartialEq, Encode, Decode)]
+struct OverrideSink {
+    #[encdec(enc = "blob_enc", dec = "blob_dec", sink)]
+    a: [u8; 200],
+    #[encdec(with = "blob_ovr", sink)]
+    b: [u8; 200],
+}
+
+pub fn blob_enc<S: Sink + ?Sized>(v: &[u8; 200], sink: &mut S) -> Result<usize, Error> {
+    sink.write_bytes(&[0xFF])?;
+    sink.write_bytes(v)?;
+    Ok(201)
+}
+
+pub fn blob_dec(buff: &[u8]) -> Result<([u8; 200], usize), Error> {
+    let (v, n) = <[u8; 200]>::decode_owned(buff.get(1..).ok_or(Error::Length)?)?;
+    Ok((v, n + 1))
+}
+
+mod blob_ovr {
+    pub use super::{blob_dec as dec, blob_enc as enc};
+}
+
+#[test]
+fn override_sink() {
+    let mut buff = [0u8; 512];
+
+    let v = OverrideSink {
+        a: [random(); 200],
+        b: [random(); 200],
+    };
+
+    // Objects larger than the scratch buffer are counted and encoded in place
+    assert_eq!(v.encode_len(), Ok(402));
+    test_encode_decode(&mut buff, v);
+    assert_eq!(buff[0], 0xFF);
+    assert_eq!(buff[201], 0xFF);
+}
+
+/// Mismatched encode length, checked in debug builds
+#[derive(Debug, PartialEq, Encode)]
+#[encdec(check_len)]
+struct CheckLen {
+    b: u8,
+    #[encdec(enc = "u64_enc", enc_len = "u64_bad_len")]
+    a: u64,
+}
+
+fn u64_bad_len(_v: &u64) -> Result<usize, Error> {
+    Ok(8)
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "CheckLen.a encode_len does not match encoded length")]
+fn check_len_mismatch() {
+    let mut buff = [0u8; 256];
+
+    let _ = CheckLen { b: 1, a: 2 }.encode(&mut buff);
+}
+
+/// `enc` overrides without `enc_len`, with lengths computed via scratch buffers
+#[derive(Debug, PartialEq, Encode)]
+#[encdec(check_len)]
+struct ScratchLen {
+    b: u8,
+    #[encdec(enc = "u64_enc")]
+    a: u64,
+}
+
+#[test]
+fn scratch_len_override() {
+    let mut buff = [0u8; 16];
+
+    let t = ScratchLen { b: 1, a: 0x0102 };
+    assert_eq!(t.encode_len(), Ok(10));
+    assert_eq!(t.encode(&mut buff), Ok(10));
+    assert_eq!(&buff[..10], &[1, 0xFF, 0, 0, 0, 0, 0, 0, 1, 2]);
+}
+
+/// Manual implementation relying on the default `encode_len`
+#[derive(Debug)]
+struct Repeated(u8, usize);
+
+impl Encode for Repeated {
+    type Error = Error;
+
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, Error> {
+        let available = buff.len();
+        let b = buff.get_mut(..self.1).ok_or(Error::BufferTooSmall {
+            needed: self.1,
+            available,
+            offset: 0,
+        })?;
+        b.fill(self.0);
+        Ok(self.1)
+    }
+}
+
+#[test]
+fn default_encode_len() {
+    assert_eq!(Repeated(1, 3).encode_len(), Ok(3));
+
+    // Objects larger than the stack scratch buffer are counted on the heap
+    #[cfg(feature = "alloc")]
+    assert_eq!(Repeated(1, 1000).encode_len(), Ok(1000));
+    #[cfg(not(feature = "alloc"))]
+    assert!(Repeated(1, 1000).encode_len().is_err());
+}
+
 mod u64_ovr {
     use encdec::Error;
 
@@ -229,8 +636,8 @@ enum NewError {
 impl From<encdec::Error> for NewError {
     fn from(e: encdec::Error) -> Self {
         match e {
-            Error::Length => Self::Length,
             Error::Utf8 => Self::Utf8,
+            _ => Self::Length,
         }
     }
 }
@@ -262,7 +669,21 @@ struct WithConst<const N: usize = 4> {
     a: [u8; N],
 }
 
+#[allow(dead_code)]
 #[derive(Clone, Debug, PartialEq, Encode, Decode)]
 struct SomeGeneric<M: EncDecOwned + Debug> {
     m: M,
 }
+
+/// Generic fields with path-qualified bounds, matched by all derives
+#[derive(Clone, Debug, PartialEq, Encode, DecodeOwned)]
+struct Qualified<T: encdec::Encode<Error = Error> + encdec::DecodeOwned<Error = Error>> {
+    t: T,
+}
+
+#[test]
+fn qualified_generic_bounds() {
+    let mut buff = [0u8; 16];
+
+    test_encode_decode(&mut buff, Qualified { t: 0x0302u16 });
+}
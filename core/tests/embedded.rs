@@ -0,0 +1,159 @@
+#![cfg(feature = "embedded-io")]
+
+use std::collections::VecDeque;
+
+use embedded_io::{ErrorKind, ErrorType};
+
+use encdec::{
+    embedded::{decode_from_reader, encode_to_writer, IoError},
+    DecodeOwned, Encode, Error,
+};
+
+#[derive(Debug, PartialEq, Encode, DecodeOwned)]
+struct Message {
+    id: u8,
+    value: u32,
+    crc: u16,
+}
+
+/// Mock serial driver, reading a single byte per call
+#[derive(Default)]
+struct Uart {
+    tx: Vec<u8>,
+    rx: VecDeque<u8>,
+}
+
+impl ErrorType for Uart {
+    type Error = ErrorKind;
+}
+
+impl embedded_io::Read for Uart {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        match (self.rx.pop_front(), buf.first_mut()) {
+            (Some(b), Some(o)) => {
+                *o = b;
+                Ok(1)
+            }
+            _ => Ok(0),
+        }
+    }
+}
+
+impl embedded_io::Write for Uart {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.tx.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+fn message() -> Message {
+    Message {
+        id: 1,
+        value: 0x04030201,
+        crc: 0xabcd,
+    }
+}
+
+#[test]
+fn embedded_encode_decode() {
+    let mut scratch = [0u8; 16];
+    let mut uart = Uart::default();
+
+    let m = message();
+    assert_eq!(encode_to_writer(&m, &mut uart, &mut scratch), Ok(7));
+    assert_eq!(uart.tx, [0x01, 0x01, 0x02, 0x03, 0x04, 0xcd, 0xab]);
+
+    // Loop back with trailing data, which must not be consumed
+    uart.rx.extend(uart.tx.iter());
+    uart.rx.push_back(0xff);
+
+    let d = decode_from_reader::<Message, _>(&mut uart, &mut scratch).unwrap();
+    assert_eq!(d, m);
+    assert_eq!(uart.rx, [0xff]);
+}
+
+#[test]
+fn embedded_errors() {
+    let mut uart = Uart::default();
+
+    // Scratch too small to encode
+    let mut scratch = [0u8; 4];
+    assert!(matches!(
+        encode_to_writer(&message(), &mut uart, &mut scratch),
+        Err(IoError::Codec(Error::BufferTooSmall { .. }))
+    ));
+
+    // Scratch too small to decode
+    uart.rx.extend([0u8; 7]);
+    assert!(matches!(
+        decode_from_reader::<Message, _>(&mut uart, &mut scratch),
+        Err(IoError::Codec(Error::BufferTooSmall { .. }))
+    ));
+
+    // Reader ends before object is complete
+    let mut scratch = [0u8; 16];
+    let mut uart = Uart::default();
+    uart.rx.extend([0u8; 3]);
+    assert_eq!(
+        decode_from_reader::<Message, _>(&mut uart, &mut scratch),
+        Err(IoError::UnexpectedEof)
+    );
+}
+
+#[cfg(feature = "embedded-io-async")]
+mod asynch {
+    use core::{
+        future::Future,
+        pin::pin,
+        task::{Context, Poll, Waker},
+    };
+
+    use encdec::embedded::{decode_from_reader_async, encode_to_writer_async};
+
+    use super::*;
+
+    impl embedded_io_async::Read for Uart {
+        async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+            embedded_io::Read::read(self, buf)
+        }
+    }
+
+    impl embedded_io_async::Write for Uart {
+        async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            embedded_io::Write::write(self, buf)
+        }
+    }
+
+    /// Poll a future that is always ready to completion
+    fn block_on<F: Future>(f: F) -> F::Output {
+        let mut f = pin!(f);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(v) = f.as_mut().poll(&mut cx) {
+                return v;
+            }
+        }
+    }
+
+    #[test]
+    fn embedded_async_encode_decode() {
+        let mut scratch = [0u8; 16];
+        let mut uart = Uart::default();
+
+        let m = message();
+        let n = block_on(encode_to_writer_async(&m, &mut uart, &mut scratch));
+        assert_eq!(n, Ok(7));
+
+        uart.rx.extend(uart.tx.iter());
+
+        let d = block_on(decode_from_reader_async::<Message, _>(
+            &mut uart,
+            &mut scratch,
+        ));
+        assert_eq!(d, Ok(m));
+    }
+}
@@ -0,0 +1,121 @@
+//! Tests for [`std::io`] adapters
+
+#![cfg(feature = "std")]
+
+use std::io::{ErrorKind, Read};
+
+use encdec::{io::DecodeReader, io::IoError, DecodeOwnedExt, Encode, EncodeExt, Error};
+
+#[derive(Debug, PartialEq, Encode, encdec::DecodeOwned)]
+#[encdec(validate = "Message::check")]
+struct Message {
+    id: u8,
+    value: u32,
+}
+
+impl Message {
+    fn check(&self) -> Result<(), Error> {
+        match self.id {
+            0 => Err(Error::InvalidValue { offset: 0 }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Reader returning a single byte per call
+struct Trickle<'a>(&'a [u8]);
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match (self.0.split_first(), buf.first_mut()) {
+            (Some((b, rest)), Some(o)) => {
+                *o = *b;
+                self.0 = rest;
+                Ok(1)
+            }
+            _ => Ok(0),
+        }
+    }
+}
+
+fn messages() -> Vec<Message> {
+    (1..=4)
+        .map(|i| Message {
+            id: i,
+            value: i as u32 * 1000,
+        })
+        .collect()
+}
+
+#[test]
+fn encode_to_writer() {
+    let mut w = Vec::new();
+    for m in messages() {
+        assert_eq!(m.encode_to_writer(&mut w).unwrap(), 5);
+    }
+    assert_eq!(w.len(), 20);
+
+    // Objects larger than the stack buffer
+    let big = vec![0xabu8; 1000];
+    let mut w = Vec::new();
+    assert_eq!(big.encode_to_writer(&mut w).unwrap(), 1000);
+    assert_eq!(w, big);
+}
+
+#[test]
+fn decode_from_reader_exact() {
+    let mut w = Vec::new();
+    for m in messages() {
+        m.encode_to_writer(&mut w).unwrap();
+    }
+
+    // Each decode consumes only the bytes for a single message
+    let mut r = &w[..];
+    for m in messages() {
+        assert_eq!(Message::decode_from_reader(&mut r).unwrap(), m);
+    }
+    assert!(r.is_empty());
+
+    // Truncated input
+    let mut r = &w[..3];
+    match Message::decode_from_reader(&mut r) {
+        Err(IoError::Io(e)) => assert_eq!(e.kind(), ErrorKind::UnexpectedEof),
+        r => panic!("unexpected result: {r:?}"),
+    }
+}
+
+#[test]
+fn decode_reader_stream() {
+    let mut w = Vec::new();
+    for m in messages() {
+        m.encode_to_writer(&mut w).unwrap();
+    }
+
+    let r = DecodeReader::<_, Message>::new(Trickle(&w));
+    let decoded: Vec<_> = r.map(|v| v.unwrap()).collect();
+    assert_eq!(decoded, messages());
+}
+
+#[test]
+fn decode_reader_errors() {
+    let mut w = Vec::new();
+    Message { id: 1, value: 2 }
+        .encode_to_writer(&mut w)
+        .unwrap();
+    Message { id: 0, value: 2 }
+        .encode_to_writer(&mut w)
+        .unwrap();
+
+    let mut r = DecodeReader::<_, Message>::new(&w[..]);
+    assert_eq!(r.next().unwrap().unwrap(), Message { id: 1, value: 2 });
+    assert!(matches!(
+        r.next(),
+        Some(Err(IoError::Codec(Error::InvalidValue { offset: 0 })))
+    ));
+    assert!(r.next().is_none());
+
+    // Truncated streams
+    let mut r = DecodeReader::<_, Message>::new(&w[..3]);
+    assert!(matches!(r.next(), Some(Err(IoError::Io(_)))));
+    assert!(r.next().is_none());
+}
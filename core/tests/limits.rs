@@ -0,0 +1,167 @@
+//! Tests for decoding with configurable [`DecodeLimits`]
+
+#![cfg(feature = "alloc")]
+
+use encdec::{decode::DecodeLimits, Decode, DecodeOwned, Encode, Error, Limit};
+
+#[derive(Debug, PartialEq, Encode, DecodeOwned)]
+struct Items {
+    header: u8,
+    values: Vec<u16>,
+}
+
+/// Recursive object, each byte nests a further level
+#[derive(Debug, PartialEq, Encode, DecodeOwned)]
+struct Node {
+    value: u8,
+    children: Vec<Node>,
+}
+
+#[derive(Debug, PartialEq, Encode, DecodeOwned)]
+struct Outer {
+    tag: u8,
+    inner: Inner,
+}
+
+#[derive(Debug, PartialEq, Encode, DecodeOwned)]
+struct Inner {
+    a: u8,
+    b: u8,
+}
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+struct Named<'a> {
+    #[encdec(length_of = "name")]
+    len: u8,
+    #[encdec(length = "len")]
+    name: &'a str,
+}
+
+#[test]
+fn unlimited_by_default() {
+    let buff = [0xaa, 1, 0, 2, 0, 3, 0];
+
+    let (v, n) = Items::decode(&buff).unwrap();
+    assert_eq!(n, buff.len());
+
+    let mut limits = DecodeLimits::default();
+    assert_eq!(Items::decode_limited(&buff, &mut limits), Ok((v, n)));
+    assert_eq!(limits.depth(), 0);
+    assert_eq!(limits.allocated(), 3 * core::mem::size_of::<u16>());
+}
+
+#[test]
+fn max_elements() {
+    let buff = [0xaa, 1, 0, 2, 0, 3, 0];
+
+    let mut limits = DecodeLimits::new().with_max_elements(3);
+    assert!(Items::decode_limited(&buff, &mut limits).is_ok());
+
+    let mut limits = DecodeLimits::new().with_max_elements(2);
+    assert_eq!(
+        Items::decode_limited(&buff, &mut limits),
+        Err(Error::LimitExceeded {
+            limit: Limit::Elements,
+            offset: 5
+        })
+    );
+}
+
+#[test]
+fn max_alloc() {
+    let buff = [0xaa, 1, 0, 2, 0, 3, 0];
+
+    let mut limits = DecodeLimits::new().with_max_alloc(4);
+    assert_eq!(
+        Items::decode_limited(&buff, &mut limits),
+        Err(Error::LimitExceeded {
+            limit: Limit::Alloc,
+            offset: 5
+        })
+    );
+}
+
+#[test]
+fn max_depth() {
+    // Each node nests an object and a collection
+    let buff = [1u8; 8];
+
+    let mut limits = DecodeLimits::new().with_max_depth(16);
+    let (_v, n) = Node::decode_limited(&buff, &mut limits).unwrap();
+    assert_eq!(n, buff.len());
+
+    let mut limits = DecodeLimits::new().with_max_depth(8);
+    let e = Node::decode_limited(&buff, &mut limits).unwrap_err();
+    assert!(matches!(
+        e,
+        Error::LimitExceeded {
+            limit: Limit::Depth,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn reuse_after_failure() {
+    let mut limits = DecodeLimits::new().with_max_depth(2);
+
+    // Failed decodes restore the nesting depth
+    for _ in 0..3 {
+        assert!(Outer::decode_limited(&[1, 2], &mut limits).is_err());
+        assert_eq!(limits.depth(), 0);
+    }
+
+    assert_eq!(
+        Outer::decode_limited(&[1, 2, 3], &mut limits),
+        Ok((
+            Outer {
+                tag: 1,
+                inner: Inner { a: 2, b: 3 }
+            },
+            3
+        ))
+    );
+
+    // Allocations are cumulative until reset
+    let buff = [0xaa, 1, 0, 2, 0];
+    let mut limits = DecodeLimits::new().with_max_alloc(6);
+    assert!(Items::decode_limited(&buff, &mut limits).is_ok());
+    assert!(Items::decode_limited(&buff, &mut limits).is_err());
+    assert_eq!(limits.depth(), 0);
+
+    limits.reset();
+    assert_eq!(limits.allocated(), 0);
+    assert!(Items::decode_limited(&buff, &mut limits).is_ok());
+}
+
+#[test]
+fn max_str_len() {
+    let v = Named {
+        len: 5,
+        name: "hello",
+    };
+
+    let mut buff = [0u8; 16];
+    let n = v.encode(&mut buff).unwrap();
+
+    let mut limits = DecodeLimits::new().with_max_str_len(5);
+    assert_eq!(Named::decode_limited(&buff[..n], &mut limits), Ok((v, n)));
+
+    let mut limits = DecodeLimits::new().with_max_str_len(4);
+    assert_eq!(
+        Named::decode_limited(&buff[..n], &mut limits),
+        Err(Error::LimitExceeded {
+            limit: Limit::StrLen,
+            offset: 1
+        })
+    );
+}
+
+#[test]
+fn limit_display() {
+    let e = Error::LimitExceeded {
+        limit: Limit::Elements,
+        offset: 3,
+    };
+    assert_eq!(format!("{e}"), "element limit exceeded at offset 3");
+}
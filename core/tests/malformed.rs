@@ -0,0 +1,203 @@
+//! Regression tests for decoding / encoding malformed or hostile input without panicking
+
+use encdec::{
+    decode::{DecodeExt, DecodePrefixed},
+    encode::EncodePrefixed,
+    Decode, DecodeOwned, Encode, Error,
+};
+
+/// Object reporting a larger encoded / decoded length than available
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct Liar;
+
+impl Encode for Liar {
+    type Error = Error;
+
+    fn encode_len(&self) -> Result<usize, Self::Error> {
+        Ok(1)
+    }
+
+    fn encode(&self, _buff: &mut [u8]) -> Result<usize, Self::Error> {
+        Ok(100)
+    }
+}
+
+impl DecodeOwned for Liar {
+    type Output = Liar;
+    type Error = Error;
+
+    fn decode_owned(_buff: &[u8]) -> Result<(Self::Output, usize), Self::Error> {
+        Ok((Liar, 100))
+    }
+}
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+struct WithLiar {
+    a: Liar,
+    b: u8,
+}
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+struct Refs<'a> {
+    #[encdec(length_of = "a")]
+    l: u8,
+
+    #[encdec(length = "l")]
+    a: &'a [u8],
+}
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+struct Nested<'a> {
+    a: u16,
+    b: Refs<'a>,
+    c: [u8; 4],
+}
+
+#[test]
+fn decode_prefixed_short() {
+    // Prefix indicates more data than available
+    let buff = [0x05, 0x01, 0x02];
+
+    assert_eq!(
+        <u16 as DecodePrefixed<u8>>::decode_prefixed(&buff),
+        Err(Error::Incomplete {
+            needed: 3,
+            offset: 1
+        })
+    );
+}
+
+#[test]
+fn encode_prefixed_overflow() {
+    let mut buff = [0u8; 512];
+    let v = [0u8; 300];
+
+    assert_eq!(
+        <[u8; 300] as EncodePrefixed<u8>>::encode_prefixed(&v, &mut buff),
+        Err(Error::Overflow { offset: 0 })
+    );
+}
+
+#[test]
+fn encode_length_of_overflow() {
+    let mut buff = [0u8; 512];
+    let t = Refs {
+        l: 0,
+        a: &[0u8; 300],
+    };
+
+    assert_eq!(t.encode(&mut buff), Err(Error::Overflow { offset: 0 }));
+}
+
+#[test]
+fn decode_array_overrun() {
+    let buff = [0u8; 4];
+
+    assert!(<[Liar; 2]>::decode(&buff).is_ok());
+}
+
+#[test]
+fn decode_derived_overrun() {
+    let buff = [0u8; 4];
+
+    assert_eq!(
+        WithLiar::decode(&buff),
+        Err(Error::Incomplete {
+            needed: 1,
+            offset: 100
+        })
+    );
+}
+
+#[test]
+fn encode_derived_overrun() {
+    let mut buff = [0u8; 4];
+
+    assert!(WithLiar { a: Liar, b: 1 }.encode(&mut buff).is_err());
+    assert!([Liar, Liar].encode(&mut buff).is_ok());
+}
+
+#[test]
+fn decode_iter_overrun() {
+    let buff = [0u8; 4];
+
+    let mut i = Liar::decode_iter(&buff);
+    assert_eq!(i.next(), Some(Ok(Liar)));
+    assert_eq!(i.next(), None);
+}
+
+#[test]
+fn decode_iter_zero_length() {
+    let buff = [0u8; 4];
+
+    let mut i = <()>::decode_iter(&buff);
+    assert_eq!(i.next(), Some(Err(Error::InvalidValue { offset: 0 })));
+    assert_eq!(i.next(), None);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn decode_vec_zero_length() {
+    let buff = [0u8; 4];
+
+    assert_eq!(
+        <Vec<()>>::decode(&buff),
+        Err(Error::InvalidValue { offset: 0 })
+    );
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn decode_heapless_vec_zero_length() {
+    let buff = [0u8; 4];
+
+    assert_eq!(
+        <heapless::Vec<(), 8>>::decode(&buff),
+        Err(Error::InvalidValue { offset: 0 })
+    );
+}
+
+#[test]
+fn decode_truncated() {
+    let mut buff = [0u8; 64];
+
+    let t = Nested {
+        a: 0x1234,
+        b: Refs {
+            l: 3,
+            a: &[1, 2, 3],
+        },
+        c: [4, 5, 6, 7],
+    };
+    let n = t.encode(&mut buff).unwrap();
+
+    // Every truncation of a valid encoding must fail cleanly
+    for i in 0..n {
+        assert!(Nested::decode(&buff[..i]).is_err(), "truncated at {}", i);
+    }
+
+    // As must every corruption of the length field
+    for l in 0..=255u8 {
+        buff[2] = l;
+        let _ = Nested::decode(&buff[..n]);
+    }
+}
+
+#[test]
+fn encode_truncated() {
+    let mut buff = [0u8; 64];
+
+    let t = Nested {
+        a: 0x1234,
+        b: Refs {
+            l: 3,
+            a: &[1, 2, 3],
+        },
+        c: [4, 5, 6, 7],
+    };
+    let n = t.encode_len().unwrap();
+
+    for i in 0..n {
+        assert!(t.encode(&mut buff[..i]).is_err(), "truncated at {}", i);
+    }
+}
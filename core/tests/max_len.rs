@@ -0,0 +1,81 @@
+//! Tests for compile-time encoded length bounds
+
+#![cfg(all(feature = "alloc", feature = "heapless"))]
+
+use core::marker::PhantomData;
+
+use encdec::{
+    encode::{FixedEncodedLen, MaxEncodedLen},
+    Encode, EncodeExt, Error,
+};
+
+#[derive(Debug, PartialEq, Encode)]
+#[encdec(size = 7)]
+struct Header {
+    kind: u8,
+    seq: u32,
+    crc: u16,
+}
+
+#[derive(Debug, PartialEq, Encode)]
+struct Message {
+    header: Header,
+    #[encdec(length_of = "data")]
+    len: u8,
+    data: heapless::Vec<u16, 8>,
+}
+
+#[derive(Debug, PartialEq, Encode)]
+#[encdec(size = 9)]
+struct Borrowed<'a> {
+    id: u8,
+    key: &'a [u8; 8],
+}
+
+#[derive(Debug, PartialEq, Encode)]
+struct Generic<T: Encode<Error = Error>> {
+    id: u16,
+    values: [T; 2],
+    _marker: PhantomData<T>,
+}
+
+#[test]
+fn primitive_lens() {
+    assert_eq!(u8::ENCODED_LEN, 1);
+    assert_eq!(i64::MAX_ENCODED_LEN, 8);
+    assert_eq!(<[u32; 4]>::ENCODED_LEN, 16);
+    assert_eq!(<heapless::Vec<u32, 4>>::MAX_ENCODED_LEN, 16);
+    assert_eq!(<()>::ENCODED_LEN, 0);
+}
+
+#[test]
+fn derived_lens() {
+    assert_eq!(Header::ENCODED_LEN, 7);
+    assert_eq!(Header::MAX_ENCODED_LEN, 7);
+    assert_eq!(Message::MAX_ENCODED_LEN, 7 + 1 + 16);
+    assert_eq!(Borrowed::ENCODED_LEN, 9);
+    assert_eq!(Generic::<u32>::ENCODED_LEN, 2 + 8);
+    assert_eq!(Generic::<Header>::MAX_ENCODED_LEN, 2 + 14);
+
+    // Static buffers may be sized from a type
+    let m = Message {
+        header: Header {
+            kind: 1,
+            seq: 2,
+            crc: 3,
+        },
+        len: 0,
+        data: heapless::Vec::from_slice(&[0xabcd; 8]).unwrap(),
+    };
+    let (buff, n) = m.encode_buff::<{ Message::MAX_ENCODED_LEN }>().unwrap();
+    assert_eq!(n, Message::MAX_ENCODED_LEN);
+    assert_eq!(&buff[..n], &m.encode_vec().unwrap().0[..]);
+
+    // Fixed layouts encode to exactly the declared length
+    let g = Generic {
+        id: 1,
+        values: [2u32, 3],
+        _marker: PhantomData,
+    };
+    assert_eq!(g.encode_len(), Ok(Generic::<u32>::ENCODED_LEN));
+}
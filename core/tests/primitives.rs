@@ -1,6 +1,6 @@
 use rand::random;
 
-use encdec::helpers::test_encode_decode;
+use encdec::{helpers::test_encode_decode, DecodeExt, DecodeOwned, DecodeOwnedExt, Error};
 
 #[test]
 fn encode_decode_u8() {
@@ -49,3 +49,116 @@ fn encode_decode_i64() {
     let mut buff = [0u8; 256];
     test_encode_decode::<i64>(&mut buff, random());
 }
+
+#[test]
+fn encode_decode_bool() {
+    let mut buff = [0u8; 256];
+    test_encode_decode::<bool>(&mut buff, random());
+
+    // Values other than `0` / `1` are rejected as invalid tags
+    assert_eq!(bool::decode_owned(&[1]), Ok((true, 1)));
+    assert_eq!(
+        bool::decode_owned(&[2]),
+        Err(Error::InvalidTag { offset: 0 })
+    );
+}
+
+#[test]
+fn encode_decode_unit() {
+    let mut buff = [0u8; 256];
+    test_encode_decode::<()>(&mut buff, ());
+}
+
+#[test]
+fn encode_decode_phantom() {
+    let mut buff = [0u8; 256];
+    test_encode_decode::<core::marker::PhantomData<u32>>(&mut buff, core::marker::PhantomData);
+}
+
+#[test]
+fn decode_ref_pod() {
+    use encdec::decode::Pod;
+
+    let buff = [0x11, 0x22, 0x33, 0x44, 0x55];
+    let (v, n) = <[u8; 4]>::decode_ref(&buff).unwrap();
+
+    assert_eq!(n, 4);
+    assert_eq!(v, &[0x11, 0x22, 0x33, 0x44]);
+    assert_eq!(v.as_ptr(), buff.as_ptr());
+}
+
+#[test]
+fn decode_le_views() {
+    use encdec::decode::{DecodedTagged, U16Le, U32Le};
+
+    let buff = [0x01, 0x02, 0x03, 0x04, 0x05, 0x06];
+
+    // Multi-byte values are borrowed via little-endian wrappers
+    let v = <&[U16Le]>::decode_len(&buff, 6).unwrap();
+    assert_eq!(v.as_ptr() as *const u8, buff.as_ptr());
+    assert_eq!(
+        v.iter().map(|v| v.get()).collect::<Vec<_>>(),
+        [0x0201, 0x0403, 0x0605]
+    );
+
+    // Lengths must be a multiple of the element size
+    assert_eq!(
+        <&[U32Le]>::decode_len(&buff, 6),
+        Err(Error::InvalidValue { offset: 0 })
+    );
+
+    // Wrappers round-trip as their native values
+    let mut b = [0u8; 4];
+    test_encode_decode(&mut b, U32Le::new(0x12345678));
+    assert_eq!(b, 0x12345678u32.to_le_bytes());
+}
+
+#[test]
+fn decode_incomplete() {
+    let buff = [0u8; 3];
+
+    assert_eq!(
+        u32::decode_owned(&buff),
+        Err(Error::Incomplete {
+            needed: 1,
+            offset: 0
+        })
+    );
+}
+
+#[test]
+fn error_display() {
+    let e = Error::BufferTooSmall {
+        needed: 4,
+        available: 2,
+        offset: 3,
+    };
+    assert_eq!(
+        e.to_string(),
+        "buffer too small at offset 3 (needed 4 bytes, available 2)"
+    );
+
+    assert_eq!(e.offset_by(4).offset(), Some(7));
+    assert_eq!(Error::Utf8.offset_by(4).offset(), None);
+}
+
+#[test]
+fn decode_exact() {
+    let buff = [0x01, 0x02, 0x03];
+
+    assert_eq!(u16::decode_exact(&buff[..2]), Ok(0x0201));
+    assert_eq!(
+        u16::decode_exact(&buff),
+        Err(Error::TrailingBytes {
+            remaining: 1,
+            offset: 2
+        })
+    );
+    assert_eq!(
+        <[u8; 2]>::decode_owned_exact(&buff),
+        Err(Error::TrailingBytes {
+            remaining: 1,
+            offset: 2
+        })
+    );
+}
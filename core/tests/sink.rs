@@ -0,0 +1,220 @@
+//! Tests for encoding to generic sinks with [`EncodeSink`]
+
+#![cfg(all(feature = "std", feature = "heapless"))]
+
+use std::collections::hash_map::DefaultHasher;
+
+use encdec::{
+    encode::{encode_with_sink, Counter, EncodeSink, FnSink, HashSink, Sink},
+    io::IoSink,
+    ContextError, Encode, EncodeExt, Error,
+};
+
+#[derive(Debug, PartialEq, Encode, EncodeSink)]
+struct Header {
+    kind: u8,
+    seq: u32,
+}
+
+#[derive(Debug, PartialEq, Encode, EncodeSink)]
+struct Message<'a> {
+    header: Header,
+    #[encdec(length_of = "payload")]
+    len: u8,
+    payload: Vec<u16>,
+    #[encdec(with = "be_u16")]
+    crc: u16,
+    name: &'a str,
+    #[encdec(enc = "enc_pad", enc_len = "pad_len")]
+    pad: [u8; 100],
+    #[encdec(enc = "enc_tail", sink)]
+    tail: [u8; 80],
+}
+
+/// Slice encoder larger than the stack scratch buffer
+fn enc_pad(v: &[u8; 100], buff: &mut [u8]) -> Result<usize, Error> {
+    buff.get_mut(..100).ok_or(Error::Length)?.copy_from_slice(v);
+    Ok(100)
+}
+
+fn pad_len(_v: &[u8; 100]) -> Result<usize, Error> {
+    Ok(100)
+}
+
+/// Sink encoder, streamed without a scratch buffer
+fn enc_tail<S: Sink + ?Sized>(v: &[u8; 80], sink: &mut S) -> Result<usize, Error> {
+    sink.write_bytes(v)?;
+    Ok(v.len())
+}
+
+#[derive(Debug, PartialEq, Encode, EncodeSink)]
+#[encdec(context)]
+struct Wrapped {
+    a: u16,
+    b: [u8; 4],
+    #[encdec(with = "be_u16")]
+    c: u16,
+}
+
+mod be_u16 {
+    use encdec::Error;
+
+    pub fn enc(v: &u16, buff: &mut [u8]) -> Result<usize, Error> {
+        if buff.len() < 2 {
+            return Err(Error::BufferTooSmall {
+                needed: 2,
+                available: buff.len(),
+                offset: 0,
+            });
+        }
+        buff[..2].copy_from_slice(&v.to_be_bytes());
+        Ok(2)
+    }
+
+    pub fn enc_len(_v: &u16) -> Result<usize, Error> {
+        Ok(2)
+    }
+}
+
+fn message() -> Message<'static> {
+    Message {
+        header: Header {
+            kind: 0x11,
+            seq: 0xaabbccdd,
+        },
+        len: 0,
+        payload: (0..20).collect(),
+        crc: 0x1234,
+        name: "sink",
+        pad: [0xaa; 100],
+        tail: [0x55; 80],
+    }
+}
+
+#[test]
+fn sink_matches_encode() {
+    let m = message();
+    let (full, n) = m.encode_vec().unwrap();
+
+    // Vec sink
+    let mut v = Vec::new();
+    assert_eq!(m.encode_to(&mut v), Ok(n));
+    assert_eq!(v, full);
+
+    // Counting sink
+    let mut c = Counter::new();
+    assert_eq!(m.encode_to(&mut c), Ok(n));
+    assert_eq!(c.count(), m.encode_len().unwrap());
+    assert_eq!(m.counted_len(), Ok(n));
+
+    // Slice wrapper
+    let mut buff = vec![0u8; n];
+    assert_eq!(encode_with_sink(&m, &mut buff), Ok(n));
+    assert_eq!(buff, full);
+
+    // Checksum via function sink
+    let mut sum = 0u32;
+    m.encode_to(&mut FnSink::new(|d: &[u8]| {
+        sum = d.iter().fold(sum, |a, b| a + *b as u32)
+    }))
+    .unwrap();
+    assert_eq!(sum, full.iter().map(|b| *b as u32).sum::<u32>());
+
+    // Hashing is repeatable
+    let mut h1 = HashSink::new(DefaultHasher::new());
+    let mut h2 = HashSink::new(DefaultHasher::new());
+    m.encode_to(&mut h1).unwrap();
+    message().encode_to(&mut h2).unwrap();
+    assert_eq!(h1.finish(), h2.finish());
+}
+
+#[test]
+fn sink_errors() {
+    let m = message();
+    let n = m.encode_len().unwrap();
+
+    // Undersized buffers are rejected prior to writing
+    let mut buff = [0xffu8; 8];
+    assert_eq!(
+        encode_with_sink(&m, &mut buff),
+        Err(Error::BufferTooSmall {
+            needed: n,
+            available: 8,
+            offset: 0
+        })
+    );
+    assert_eq!(buff, [0xff; 8]);
+
+    // Fixed capacity sinks report the failing write position
+    let mut v = heapless::Vec::<u8, 8>::new();
+    assert_eq!(
+        m.encode_to(&mut v),
+        Err(Error::BufferTooSmall {
+            needed: 2,
+            available: 0,
+            offset: 8
+        })
+    );
+
+    // Sink errors retain context paths
+    let w = Wrapped {
+        a: 1,
+        b: [1, 2, 3, 4],
+        c: 2,
+    };
+    let mut v = heapless::Vec::<u8, 4>::new();
+    let e: ContextError = w.encode_to(&mut v).unwrap_err();
+    assert_eq!(
+        e.to_string(),
+        "Wrapped.b @ 4: buffer too small at offset 4 (needed 1 bytes, available 0)"
+    );
+
+    // Including failed writes of staged overrides
+    let mut v = heapless::Vec::<u8, 7>::new();
+    let e: ContextError = w.encode_to(&mut v).unwrap_err();
+    assert_eq!(
+        e.to_string(),
+        "Wrapped.c @ 6: buffer too small at offset 6 (needed 2 bytes, available 1)"
+    );
+}
+
+/// Writer accepting a fixed number of bytes
+struct Limited(usize);
+
+impl std::io::Write for Limited {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self.0 {
+            0 => Err(std::io::ErrorKind::WriteZero.into()),
+            _ => {
+                let n = buf.len().min(self.0);
+                self.0 -= n;
+                Ok(n)
+            }
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn sink_io() {
+    let m = message();
+
+    let mut s = IoSink::new(Vec::new());
+    let n = m.encode_to(&mut s).unwrap();
+    assert_eq!(s.written(), n);
+    assert_eq!(s.into_inner(), m.encode_vec().unwrap().0);
+
+    // IO errors are reported at the failing write with the error retained
+    let mut s = IoSink::new(Limited(4));
+    assert_eq!(m.encode_to(&mut s), Err(Error::Sink { offset: 1 }));
+    assert_eq!(
+        s.take_error().map(|e| e.kind()),
+        Some(std::io::ErrorKind::WriteZero)
+    );
+
+    // Sinks may be used directly
+    assert_eq!(s.write_bytes(&[]), Ok(()));
+}
@@ -0,0 +1,223 @@
+//! Tests for decoding non-contiguous input with [`DecodeSplit`]
+
+#![cfg(feature = "alloc")]
+
+use encdec::{
+    decode::{decode_split, decode_split_owned, DecodeLimits, DecodeSplit, Scratch, SplitBuf},
+    Decode, DecodeOwned, Encode, EncodeExt, Error, Limit,
+};
+
+#[derive(Debug, PartialEq, Encode, DecodeOwned, DecodeSplit)]
+struct Header {
+    kind: u8,
+    seq: u32,
+}
+
+#[derive(Debug, PartialEq, Encode, DecodeOwned, DecodeSplit)]
+struct Reading {
+    header: Header,
+    #[encdec(with = "be_u16")]
+    crc: u16,
+    tag: [u8; 3],
+    values: Vec<u16>,
+}
+
+#[derive(Debug, PartialEq, Encode, Decode, DecodeSplit)]
+struct Frame<'a> {
+    id: u16,
+    #[encdec(length_of = "data")]
+    len: u8,
+    #[encdec(length = "len")]
+    data: &'a [u8],
+    #[encdec(length_of = "name")]
+    name_len: u8,
+    #[encdec(length = "name_len")]
+    name: &'a str,
+}
+
+mod be_u16 {
+    use encdec::Error;
+
+    pub fn enc(v: &u16, buff: &mut [u8]) -> Result<usize, Error> {
+        buff[..2].copy_from_slice(&v.to_be_bytes());
+        Ok(2)
+    }
+
+    pub fn enc_len(_v: &u16) -> Result<usize, Error> {
+        Ok(2)
+    }
+
+    pub fn dec(buff: &[u8]) -> Result<(u16, usize), Error> {
+        match buff.get(..2) {
+            Some(b) => Ok((u16::from_be_bytes([b[0], b[1]]), 2)),
+            None => Err(Error::Incomplete {
+                needed: 2 - buff.len(),
+                offset: 0,
+            }),
+        }
+    }
+}
+
+fn reading() -> Reading {
+    Reading {
+        header: Header {
+            kind: 0x11,
+            seq: 0xaabbccdd,
+        },
+        crc: 0x1234,
+        tag: [1, 2, 3],
+        values: (0..6).map(|v| v * 0x0101).collect(),
+    }
+}
+
+#[test]
+fn split_owned_matches_decode() {
+    let r = reading();
+    let (buff, n) = r.encode_vec().unwrap();
+
+    // Decode at every split point
+    for i in 0..=n {
+        let (head, tail) = buff.split_at(i);
+        assert_eq!(
+            decode_split_owned::<Reading>(head, tail),
+            Ok((reading(), n)),
+            "split at {i}"
+        );
+    }
+
+    // Across a wrapped ring buffer
+    let mut ring = vec![0u8; 32];
+    for start in [0, 10, 25, 31] {
+        for (i, b) in buff.iter().enumerate() {
+            ring[(start + i) % 32] = *b;
+        }
+
+        let s = SplitBuf::from_ring(&ring, start, n);
+        assert_eq!(s.len(), n);
+        assert_eq!(
+            Reading::decode_split(s, &mut Scratch::default()),
+            Ok((reading(), n)),
+            "ring start {start}"
+        );
+    }
+}
+
+#[test]
+fn split_borrowed() {
+    let f = Frame {
+        id: 0x0102,
+        len: 0,
+        data: &[0xaa; 10],
+        name_len: 0,
+        name: "split",
+    };
+    let (buff, n) = f.encode_vec().unwrap();
+    let expected = Frame {
+        len: 10,
+        name_len: 5,
+        ..f
+    };
+
+    // Fields not spanning the split are borrowed in place
+    let (head, tail) = buff.split_at(14);
+    let (d, m) = decode_split::<Frame>(head, tail, &mut []).unwrap();
+    assert_eq!((&d, m), (&expected, n));
+    assert_eq!(d.data.as_ptr(), head[3..].as_ptr());
+    assert_eq!(d.name.as_ptr(), tail.as_ptr());
+
+    // Fields spanning the split are copied into scratch space
+    for i in 0..=n {
+        let (head, tail) = buff.split_at(i);
+        let mut scratch = [0u8; 16];
+        let (d, m) = decode_split::<Frame>(head, tail, &mut scratch).unwrap();
+        assert_eq!((&d, m), (&expected, n), "split at {i}");
+    }
+
+    // Insufficient scratch space is reported at the field offset
+    let (head, tail) = buff.split_at(5);
+    let mut scratch = [0u8; 4];
+    assert_eq!(
+        decode_split::<Frame>(head, tail, &mut scratch),
+        Err(Error::BufferTooSmall {
+            needed: 10,
+            available: 4,
+            offset: 3
+        })
+    );
+}
+
+#[test]
+fn split_errors() {
+    let (buff, n) = reading().encode_vec().unwrap();
+
+    // Truncated input
+    let (head, tail) = buff[..4].split_at(2);
+    assert_eq!(
+        decode_split_owned::<Header>(head, tail),
+        Err(Error::Incomplete {
+            needed: 1,
+            offset: 1
+        })
+    );
+
+    // Limits apply to split collections
+    let (head, tail) = buff.split_at(n - 3);
+    let mut limits = DecodeLimits::new().with_max_elements(4);
+    assert_eq!(
+        Reading::decode_split_limited(
+            SplitBuf::new(head, tail),
+            &mut Scratch::default(),
+            &mut limits
+        ),
+        Err(Error::LimitExceeded {
+            limit: Limit::Elements,
+            offset: 10 + 8
+        })
+    );
+
+    // Split buffers may be advanced past the split
+    let s = SplitBuf::new(&buff[..4], &buff[4..]);
+    assert_eq!(s.advance(6).head(), &buff[6..]);
+    assert!(s.advance(n + 1).is_empty());
+}
+
+#[test]
+fn split_override_remaining() {
+    let mut r = reading();
+    r.values = (0..60).collect();
+    let (buff, n) = r.encode_vec().unwrap();
+
+    // Overrides spanning the split with more than the stack buffer remaining
+    // are copied via scratch space
+    let (head, tail) = buff.split_at(6);
+    let mut scratch = [0u8; 256];
+    assert_eq!(
+        Reading::decode_split(SplitBuf::new(head, tail), &mut Scratch::new(&mut scratch)),
+        Ok((r, n))
+    );
+
+    // Failing without truncating input where scratch space is unavailable
+    assert_eq!(
+        decode_split_owned::<Reading>(head, tail),
+        Err(Error::BufferTooSmall {
+            needed: n - 5,
+            available: 64,
+            offset: 5
+        })
+    );
+}
+
+#[test]
+fn split_arrays() {
+    let v = [0x01020304u32, 0x05060708, 0x090a0b0c];
+    let (buff, n) = v.encode_vec().unwrap();
+
+    for i in 0..=n {
+        let (head, tail) = buff.split_at(i);
+        assert_eq!(
+            decode_split_owned::<[u32; 3]>(head, tail),
+            Ok((v, n)),
+            "split at {i}"
+        );
+    }
+}
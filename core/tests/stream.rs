@@ -0,0 +1,132 @@
+//! Tests for incremental decoding with [`StreamDecoder`]
+
+#![cfg(all(feature = "alloc", feature = "heapless"))]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use encdec::{
+    decode::{DecodeStatus, StreamDecoder},
+    DecodeOwned, Encode, EncodeExt, Error,
+};
+
+#[derive(Debug, PartialEq, Encode, DecodeOwned)]
+#[encdec(validate = "Message::check")]
+struct Message {
+    id: u8,
+    value: u32,
+}
+
+impl Message {
+    fn check(&self) -> Result<(), Error> {
+        match self.id {
+            0 => Err(Error::InvalidValue { offset: 0 }),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn encoded() -> Vec<u8> {
+    let mut b = vec![];
+    for i in 1..=3 {
+        let (e, n) = Message {
+            id: i,
+            value: i as u32,
+        }
+        .encode_buff::<8>()
+        .unwrap();
+        b.extend_from_slice(&e[..n]);
+    }
+    b
+}
+
+#[test]
+fn stream_decode_trickle() {
+    let mut d = StreamDecoder::<Message, Vec<u8>>::new();
+    let mut decoded = vec![];
+
+    assert_eq!(d.poll(), Ok(DecodeStatus::NeedMore(1)));
+
+    for b in encoded() {
+        assert_eq!(d.push(&[b]), 1);
+
+        while let DecodeStatus::Complete(m) = d.poll().unwrap() {
+            decoded.push(m);
+        }
+    }
+
+    assert_eq!(decoded.len(), 3);
+    assert_eq!(decoded[2], Message { id: 3, value: 3 });
+    assert!(d.buffered().is_empty());
+}
+
+#[test]
+fn stream_decode_heapless() {
+    let mut d = StreamDecoder::<Message, heapless::Vec<u8, 8>>::new();
+
+    // Pushes are limited by buffer capacity
+    let data = encoded();
+    assert_eq!(d.push(&data), 8);
+
+    assert_eq!(
+        d.poll(),
+        Ok(DecodeStatus::Complete(Message { id: 1, value: 1 }))
+    );
+    assert_eq!(d.poll(), Ok(DecodeStatus::NeedMore(2)));
+
+    assert_eq!(d.push(&data[8..]), 5);
+    assert_eq!(
+        d.poll(),
+        Ok(DecodeStatus::Complete(Message { id: 2, value: 2 }))
+    );
+}
+
+#[test]
+fn stream_decode_errors() {
+    // Malformed data discards buffered data
+    let mut d = StreamDecoder::<Message, Vec<u8>>::new();
+    d.push(&[0x00, 0x01, 0x02, 0x03, 0x04, 0x01]);
+    assert_eq!(d.poll(), Err(Error::InvalidValue { offset: 0 }));
+    assert!(d.buffered().is_empty());
+
+    // Objects exceeding buffer capacity
+    let mut d = StreamDecoder::<Message, heapless::Vec<u8, 4>>::new();
+    d.push(&[0x01, 0x02]);
+    assert_eq!(
+        d.poll(),
+        Err(Error::BufferTooSmall {
+            needed: 5,
+            available: 4,
+            offset: 0
+        })
+    );
+}
+
+static ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
+
+/// Object counting decode attempts
+#[derive(Debug, PartialEq)]
+struct Counted(u64);
+
+impl DecodeOwned for Counted {
+    type Output = Counted;
+    type Error = Error;
+
+    fn decode_owned(buff: &[u8]) -> Result<(Self::Output, usize), Self::Error> {
+        ATTEMPTS.fetch_add(1, Ordering::SeqCst);
+        let (v, n) = u64::decode_owned(buff)?;
+        Ok((Counted(v), n))
+    }
+}
+
+#[test]
+fn stream_decode_no_reparse() {
+    let mut d = StreamDecoder::<Counted, Vec<u8>>::new();
+
+    for i in 0..8 {
+        d.push(&[i]);
+        let _ = d.poll().unwrap();
+    }
+
+    // Decode is attempted once when empty and once when complete
+    assert_eq!(ATTEMPTS.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,241 @@
+//! Tests for byte-stuffing frame layers
+
+use encdec::{
+    stuffing::{decode_frame, encode_frame, stuff, unstuff, Cobs, Hdlc, Slip, Stuffing},
+    Decode, DecodeOwned, Encode, Error,
+};
+
+#[cfg(feature = "heapless")]
+use encdec::stuffing::FrameSplitter;
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+struct Message<'a> {
+    id: u8,
+    #[encdec(length_of = "data")]
+    len: u16,
+    #[encdec(length = "len")]
+    data: &'a [u8],
+}
+
+#[derive(Debug, PartialEq, Encode, DecodeOwned)]
+struct Reading {
+    sensor: u8,
+    value: u32,
+}
+
+/// Sample inputs covering delimiters, escapes and COBS block boundaries
+fn samples() -> Vec<Vec<u8>> {
+    let mut v = vec![
+        vec![],
+        vec![0x00],
+        vec![0x00, 0x00],
+        vec![0x11, 0x22, 0x00, 0x33],
+        vec![0xc0, 0xdb, 0xdc, 0xdd],
+        vec![0x7e, 0x7d, 0x5e, 0x5d],
+        (0..=255).collect(),
+    ];
+    for n in [253, 254, 255, 508, 600] {
+        v.push((0..n).map(|i| (i % 255 + 1) as u8).collect());
+    }
+    v
+}
+
+fn round_trip<S: Stuffing>() {
+    for data in samples() {
+        let mut frame = vec![0u8; S::max_len(data.len())];
+        let n = stuff::<S>(&data, &mut frame).unwrap();
+        let frame = &frame[..n];
+
+        // Delimiters only appear at frame boundaries
+        assert_eq!(frame.last(), Some(&S::DELIMITER));
+        let inner = match frame[0] == S::DELIMITER {
+            true => &frame[1..n - 1],
+            false => &frame[..n - 1],
+        };
+        assert!(!inner.contains(&S::DELIMITER), "delimiter in {inner:02x?}");
+
+        let mut out = vec![0u8; inner.len()];
+        let m = unstuff::<S>(inner, &mut out).unwrap();
+        assert_eq!(&out[..m], &data[..], "round trip of {} bytes", data.len());
+    }
+}
+
+#[test]
+fn stuffing_round_trips() {
+    round_trip::<Cobs>();
+    round_trip::<Slip>();
+    round_trip::<Hdlc>();
+}
+
+#[test]
+fn stuffing_vectors() {
+    let mut buff = [0u8; 16];
+
+    let n = stuff::<Cobs>(&[0x11, 0x22, 0x00, 0x33], &mut buff).unwrap();
+    assert_eq!(&buff[..n], &[0x03, 0x11, 0x22, 0x02, 0x33, 0x00]);
+
+    let n = stuff::<Slip>(&[0x01, 0xc0, 0xdb], &mut buff).unwrap();
+    assert_eq!(&buff[..n], &[0xc0, 0x01, 0xdb, 0xdc, 0xdb, 0xdd, 0xc0]);
+
+    let n = stuff::<Hdlc>(&[0x7e, 0x01, 0x7d], &mut buff).unwrap();
+    assert_eq!(&buff[..n], &[0x7e, 0x7d, 0x5e, 0x01, 0x7d, 0x5d, 0x7e]);
+
+    // Full COBS blocks are not followed by an implied zero
+    let data = [0x01; 254];
+    let mut buff = [0u8; 258];
+    let n = stuff::<Cobs>(&data, &mut buff).unwrap();
+    assert_eq!(n, 257);
+    assert_eq!((buff[0], buff[255], buff[256]), (0xff, 0x01, 0x00));
+}
+
+#[test]
+fn stuffing_errors() {
+    let mut buff = [0u8; 4];
+    assert_eq!(stuff::<Cobs>(&[1, 2], &mut buff), Ok(4));
+    assert_eq!(
+        stuff::<Slip>(&[1, 2, 3], &mut buff),
+        Err(Error::BufferTooSmall {
+            needed: 8,
+            available: 4,
+            offset: 0
+        })
+    );
+
+    // Malformed frames
+    assert_eq!(
+        unstuff::<Cobs>(&[0x05, 0x01], &mut buff),
+        Err(Error::Incomplete {
+            needed: 3,
+            offset: 0
+        })
+    );
+    assert_eq!(
+        unstuff::<Cobs>(&[0x02, 0x01, 0x00], &mut buff),
+        Err(Error::InvalidValue { offset: 2 })
+    );
+    assert_eq!(
+        unstuff::<Slip>(&[0x01, 0xdb, 0x02], &mut buff),
+        Err(Error::InvalidValue { offset: 1 })
+    );
+    assert_eq!(
+        unstuff::<Hdlc>(&[0x01, 0x7d], &mut buff),
+        Err(Error::InvalidValue { offset: 1 })
+    );
+    assert_eq!(
+        unstuff::<Hdlc>(&[0x01, 0x7e], &mut buff),
+        Err(Error::InvalidValue { offset: 1 })
+    );
+}
+
+fn frame_objects<S: Stuffing>() {
+    let data: Vec<u8> = (0..=255).rev().collect();
+    let m = Message {
+        id: S::DELIMITER,
+        len: 0,
+        data: &data,
+    };
+
+    let mut buff = vec![0u8; S::max_len(m.encode_len().unwrap())];
+    let n = encode_frame::<S, _>(&m, &mut buff).unwrap();
+
+    let d = decode_frame::<S, Message>(&mut buff[..n]).unwrap();
+    assert_eq!(d.id, S::DELIMITER);
+    assert_eq!(d.len, 256);
+    assert_eq!(d.data, &data[..]);
+
+    // Undersized buffers are rejected prior to encoding
+    let mut buff = [0u8; 8];
+    assert_eq!(
+        encode_frame::<S, _>(&m, &mut buff),
+        Err(Error::BufferTooSmall {
+            needed: S::max_len(259),
+            available: 8,
+            offset: 0
+        })
+    );
+}
+
+#[test]
+fn stuffing_objects() {
+    frame_objects::<Cobs>();
+    frame_objects::<Slip>();
+    frame_objects::<Hdlc>();
+
+    // Frames must contain a single object
+    let mut buff = [0u8; 16];
+    let n = stuff::<Cobs>(&[1, 2, 3, 4, 5, 6, 7], &mut buff).unwrap();
+    assert_eq!(
+        decode_frame::<Cobs, Reading>(&mut buff[..n]),
+        Err(Error::TrailingBytes {
+            remaining: 2,
+            offset: 5
+        })
+    );
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn frame_splitter() {
+    let readings: Vec<_> = (0..8)
+        .map(|i| Reading {
+            sensor: i,
+            value: 0xc0db_7e00 + i as u32,
+        })
+        .collect();
+
+    // Build a stream of frames with leading noise
+    let mut stream = vec![0x01, 0x02, 0xc0];
+    for r in &readings {
+        let mut buff = [0u8; 12];
+        let n = encode_frame::<Slip, _>(r, &mut buff).unwrap();
+        stream.extend_from_slice(&buff[..n]);
+    }
+
+    for chunk in [1, 3, 7, 64] {
+        let mut s = FrameSplitter::<Slip, heapless::Vec<u8, 16>>::new();
+        let mut out = vec![];
+        let mut buff = [0u8; 16];
+
+        for c in stream.chunks(chunk) {
+            let mut c = c;
+            while !c.is_empty() {
+                let n = s.push(c);
+                c = &c[n..];
+
+                loop {
+                    match s.poll_decode::<Reading>(&mut buff) {
+                        Ok(Some(r)) => out.push(r),
+                        Ok(None) => break,
+                        // Leading noise fails to decode
+                        Err(e) => assert!(out.is_empty(), "unexpected {e:?}"),
+                    }
+                }
+            }
+        }
+
+        assert_eq!(out, readings, "chunk size {chunk}");
+        assert!(s.buffered().is_empty());
+    }
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn frame_splitter_overflow() {
+    let mut s = FrameSplitter::<Cobs, heapless::Vec<u8, 4>>::new();
+    let mut buff = [0u8; 4];
+
+    // Frames exceeding the buffer are discarded through the next delimiter
+    assert_eq!(s.push(&[1, 2, 3, 4, 5, 6]), 4);
+    assert_eq!(
+        s.poll(&mut buff),
+        Err(Error::BufferTooSmall {
+            needed: 5,
+            available: 4,
+            offset: 0
+        })
+    );
+    assert_eq!(s.push(&[5, 6, 0x00, 0x02, 0x07, 0x00]), 6);
+    assert_eq!(s.poll(&mut buff), Ok(Some(1)));
+    assert_eq!(buff[0], 0x07);
+    assert_eq!(s.poll(&mut buff), Ok(None));
+}
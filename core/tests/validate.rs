@@ -0,0 +1,181 @@
+//! Tests for derived validation hooks
+
+use encdec::{decode::DecodeLimits, Decode, DecodeOwned, Encode, Error};
+
+mod checks {
+    use encdec::Error;
+
+    /// Reject zero values
+    pub fn nonzero(v: &u8) -> Result<(), Error> {
+        match *v != 0 {
+            true => Ok(()),
+            false => Err(Error::InvalidValue { offset: 0 }),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+#[encdec(validate = "Range::check")]
+struct Range {
+    a: u16,
+    #[encdec(validate = "checks::nonzero")]
+    start: u8,
+    end: u8,
+}
+
+impl Range {
+    fn check(&self) -> Result<(), Error> {
+        match self.start <= self.end {
+            true => Ok(()),
+            false => Err(Error::InvalidValue { offset: 0 }),
+        }
+    }
+}
+
+#[test]
+fn validate_decode() {
+    assert_eq!(
+        Range::decode(&[0, 0, 1, 2]),
+        Ok((
+            Range {
+                a: 0,
+                start: 1,
+                end: 2
+            },
+            4
+        ))
+    );
+
+    // Field validation errors report the field offset
+    assert_eq!(
+        Range::decode(&[0, 0, 0, 2]),
+        Err(Error::InvalidValue { offset: 2 })
+    );
+
+    // Container validation
+    assert_eq!(
+        Range::decode(&[0, 0, 3, 2]),
+        Err(Error::InvalidValue { offset: 0 })
+    );
+
+    // Validation failures leave limits unchanged for reuse
+    let mut limits = DecodeLimits::new().with_max_depth(1);
+    assert!(Range::decode_limited(&[0, 0, 0, 2], &mut limits).is_err());
+    assert!(Range::decode_limited(&[0, 0, 3, 2], &mut limits).is_err());
+    assert_eq!(limits.depth(), 0);
+    assert!(Range::decode_limited(&[0, 0, 1, 2], &mut limits).is_ok());
+}
+
+#[test]
+fn validate_not_on_encode_by_default() {
+    let r = Range {
+        a: 0,
+        start: 3,
+        end: 2,
+    };
+
+    let mut buff = [0u8; 4];
+    assert_eq!(r.encode(&mut buff), Ok(4));
+}
+
+#[derive(Debug, PartialEq)]
+enum RangeError {
+    Codec(Error),
+    Empty,
+}
+
+impl From<Error> for RangeError {
+    fn from(e: Error) -> Self {
+        Self::Codec(e)
+    }
+}
+
+#[derive(Debug, PartialEq, Encode, DecodeOwned)]
+#[encdec(error = "RangeError", validate = "NonEmpty::check", validate_encode)]
+struct NonEmpty {
+    start: u8,
+    end: u8,
+}
+
+impl NonEmpty {
+    fn check(&self) -> Result<(), RangeError> {
+        match self.start < self.end {
+            true => Ok(()),
+            false => Err(RangeError::Empty),
+        }
+    }
+}
+
+#[test]
+fn validate_error_type() {
+    assert_eq!(
+        NonEmpty::decode(&[1, 2]),
+        Ok((NonEmpty { start: 1, end: 2 }, 2))
+    );
+    assert_eq!(NonEmpty::decode(&[2, 2]), Err(RangeError::Empty));
+    assert_eq!(
+        NonEmpty::decode(&[2]),
+        Err(RangeError::Codec(Error::Incomplete {
+            needed: 1,
+            offset: 0
+        }))
+    );
+}
+
+#[test]
+fn validate_encode() {
+    let mut buff = [0xffu8; 2];
+
+    let v = NonEmpty { start: 2, end: 2 };
+    assert_eq!(v.encode(&mut buff), Err(RangeError::Empty));
+
+    // Nothing is written on validation failure
+    assert_eq!(buff, [0xff, 0xff]);
+}
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+#[encdec(validate = "Frame::check")]
+struct Frame {
+    ack: bool,
+    data: [u8; 2],
+    sum: u8,
+}
+
+impl Frame {
+    fn check(&self) -> Result<(), Error> {
+        let sum = self
+            .data
+            .iter()
+            .fold(self.ack as u8, |a, b| a.wrapping_add(*b));
+        match sum == self.sum {
+            true => Ok(()),
+            false => Err(Error::ChecksumMismatch { offset: 3 }),
+        }
+    }
+}
+
+#[test]
+fn validate_checksum() {
+    assert_eq!(
+        Frame::decode(&[1, 2, 3, 6]),
+        Ok((
+            Frame {
+                ack: true,
+                data: [2, 3],
+                sum: 6
+            },
+            4
+        ))
+    );
+
+    assert_eq!(
+        Frame::decode(&[1, 2, 3, 5]),
+        Err(Error::ChecksumMismatch { offset: 3 })
+    );
+
+    // Invalid tags report the field offset
+    assert_eq!(
+        Frame::decode(&[2, 2, 3, 6]),
+        Err(Error::InvalidTag { offset: 0 })
+    );
+}
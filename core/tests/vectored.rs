@@ -0,0 +1,159 @@
+//! Tests for vectored encoding with [`EncodeVectored`]
+
+#![cfg(feature = "std")]
+
+use std::io::Write;
+
+use encdec::{
+    encode::{EncodeVectored, Sink, Vectored, VECTORED_COPY_LIMIT},
+    ContextError, Encode, EncodeExt, Error,
+};
+
+#[derive(Debug, PartialEq, Encode, EncodeVectored)]
+struct Header {
+    kind: u8,
+    seq: u32,
+}
+
+#[derive(Debug, PartialEq, Encode, EncodeVectored)]
+struct Frame<'a> {
+    header: Header,
+    #[encdec(length_of = "payload")]
+    len: u16,
+    payload: &'a [u8],
+    #[encdec(with = "be_u16")]
+    crc: u16,
+    trailer: Vec<u8>,
+}
+
+#[derive(Debug, PartialEq, Encode, EncodeVectored)]
+#[encdec(context)]
+struct Checked {
+    a: u8,
+    #[encdec(with = "be_u16")]
+    b: u16,
+}
+
+mod be_u16 {
+    use encdec::Error;
+
+    pub fn enc(v: &u16, buff: &mut [u8]) -> Result<usize, Error> {
+        if *v == 0xffff {
+            return Err(Error::InvalidValue { offset: 0 });
+        }
+        if buff.len() < 2 {
+            return Err(Error::BufferTooSmall {
+                needed: 2,
+                available: buff.len(),
+                offset: 0,
+            });
+        }
+        buff[..2].copy_from_slice(&v.to_be_bytes());
+        Ok(2)
+    }
+
+    pub fn enc_len(_v: &u16) -> Result<usize, Error> {
+        Ok(2)
+    }
+}
+
+fn frame(payload: &[u8]) -> Frame<'_> {
+    Frame {
+        header: Header {
+            kind: 0x11,
+            seq: 0xaabbccdd,
+        },
+        len: 0,
+        payload,
+        crc: 0x1234,
+        trailer: vec![0xee; 4],
+    }
+}
+
+#[test]
+fn vectored_matches_encode() {
+    let data: Vec<u8> = (0..=255).collect();
+    let f = frame(&data);
+    let (full, n) = f.encode_vec().unwrap();
+
+    let mut v = Vectored::new();
+    assert_eq!(f.encode_vectored(&mut v), Ok(n));
+    assert_eq!(v.len(), n);
+
+    // Header in scratch, payload referenced, crc and short trailer copied
+    let slices = v.io_slices();
+    assert_eq!(slices.len(), 3);
+    assert_eq!(slices[0].len(), 7);
+    assert_eq!(slices[1].as_ptr(), data.as_ptr());
+    assert_eq!(slices[2].len(), 6);
+
+    let mut out = Vec::new();
+    assert_eq!(v.write_to(&mut out).unwrap(), n);
+    assert_eq!(out, full);
+
+    // Short payloads are copied into a single segment
+    let f = frame(&data[..VECTORED_COPY_LIMIT - 1]);
+    let mut v = Vectored::new();
+    f.encode_vectored(&mut v).unwrap();
+    assert_eq!(v.io_slices().len(), 1);
+
+    let mut out = Vec::new();
+    v.write_to(&mut out).unwrap();
+    assert_eq!(out, f.encode_vec().unwrap().0);
+
+    // Output may be cleared and reused
+    v.clear();
+    assert!(v.is_empty());
+    assert!(v.io_slices().is_empty());
+}
+
+#[test]
+fn vectored_sink_and_errors() {
+    let mut v = Vectored::new();
+    v.write_bytes(&[1, 2]).unwrap();
+    v.write(&0x0403u16).unwrap();
+    assert_eq!(v.io_slices().len(), 1);
+    assert_eq!(&*v.io_slices()[0], &[1, 2, 3, 4]);
+
+    // Failed writes are discarded with the field context retained
+    let c = Checked { a: 5, b: 0xffff };
+    let e: ContextError = c.encode_vectored(&mut v).unwrap_err();
+    assert_eq!(e.error(), Error::InvalidValue { offset: 0 });
+    assert_eq!(e.to_string(), "Checked.b @ 0: invalid value at offset 0");
+    assert_eq!(v.len(), 5);
+}
+
+/// Writer accepting at most `n` bytes per call
+struct Trickle(usize, Vec<u8>);
+
+impl Write for Trickle {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = buf.len().min(self.0);
+        self.1.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn vectored_partial_writes() {
+    let data = [0x5a; 300];
+    let f = frame(&data);
+
+    let mut v = Vectored::new();
+    f.encode_vectored(&mut v).unwrap();
+
+    let mut w = Trickle(5, Vec::new());
+    assert_eq!(v.write_to(&mut w).unwrap(), v.len());
+    assert_eq!(w.1, f.encode_vec().unwrap().0);
+
+    // Writers accepting nothing are rejected
+    let mut w = Trickle(0, Vec::new());
+    assert_eq!(
+        v.write_to(&mut w).map_err(|e| e.kind()),
+        Err(std::io::ErrorKind::WriteZero)
+    );
+}
@@ -0,0 +1,18 @@
+//! Decode fuzzing for arrays, collections and length prefixed objects
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use encdec::{decode::DecodePrefixed, helpers::fuzz_roundtrip, Decode};
+
+fuzz_target!(|data: &[u8]| {
+    let mut buff = vec![0u8; data.len()];
+
+    fuzz_roundtrip::<[u16; 4]>(&mut buff, data);
+    fuzz_roundtrip::<Vec<u32>>(&mut buff, data);
+    fuzz_roundtrip::<heapless::Vec<u16, 8>>(&mut buff, data);
+
+    let _ = <&[u8; 4]>::decode(data);
+    let _ = <Vec<u16> as DecodePrefixed<u8>>::decode_prefixed(data);
+    let _ = <u32 as DecodePrefixed<u16>>::decode_prefixed(data);
+});
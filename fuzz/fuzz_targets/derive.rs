@@ -0,0 +1,42 @@
+//! Fuzzing for derived implementations, decoding arbitrary input and
+//! encoding arbitrary (attribute consistent) objects
+#![no_main]
+
+use arbitrary::{Arbitrary, Unstructured};
+use libfuzzer_sys::fuzz_target;
+
+use encdec::{
+    helpers::{fuzz_roundtrip, test_encode_decode},
+    Decode, Encode,
+};
+
+#[derive(Debug, PartialEq, Encode, Decode, encdec::derive::Arbitrary)]
+struct Header {
+    kind: u8,
+    flags: u16,
+}
+
+#[derive(Debug, PartialEq, Encode, Decode, encdec::derive::Arbitrary)]
+struct Packet<'a> {
+    header: Header,
+
+    #[encdec(length_of = "payload")]
+    len: u16,
+
+    #[encdec(length = "len")]
+    payload: &'a [u8],
+
+    crc: u32,
+}
+
+fuzz_target!(|data: &[u8]| {
+    let mut buff = vec![0u8; 1 << 17];
+
+    // Decode and re-encode arbitrary input
+    fuzz_roundtrip::<Packet>(&mut buff, data);
+
+    // Encode and decode arbitrary objects
+    if let Ok(p) = Packet::arbitrary(&mut Unstructured::new(data)) {
+        test_encode_decode(&mut buff, p);
+    }
+});
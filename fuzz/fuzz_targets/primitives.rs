@@ -0,0 +1,20 @@
+//! Decode-then-reencode fuzzing for primitive types
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use encdec::helpers::fuzz_roundtrip;
+
+fuzz_target!(|data: &[u8]| {
+    let mut buff = [0u8; 16];
+
+    fuzz_roundtrip::<u8>(&mut buff, data);
+    fuzz_roundtrip::<u16>(&mut buff, data);
+    fuzz_roundtrip::<u32>(&mut buff, data);
+    fuzz_roundtrip::<u64>(&mut buff, data);
+    fuzz_roundtrip::<i8>(&mut buff, data);
+    fuzz_roundtrip::<i16>(&mut buff, data);
+    fuzz_roundtrip::<i32>(&mut buff, data);
+    fuzz_roundtrip::<i64>(&mut buff, data);
+    fuzz_roundtrip::<()>(&mut buff, data);
+});
@@ -0,0 +1,143 @@
+//! `#[derive(Arbitrary)`] macro implementation
+
+use std::collections::HashMap;
+
+use proc_macro::TokenStream;
+
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, GenericParam, Ident, Lifetime};
+
+use crate::{attrs::FieldAttrs, fields};
+
+/// Arbitrary derive helper
+pub fn derive_arbitrary_impl(input: TokenStream) -> TokenStream {
+    let DeriveInput {
+        ident,
+        data,
+        generics,
+        ..
+    } = parse_macro_input!(input);
+
+    // Extract struct fields
+    let s = match data {
+        Data::Struct(s) => s,
+        _ => panic!("Unsupported object type for derivation"),
+    };
+
+    // Parse field attributes, reporting invalid attributes at their span
+    let field_attrs = match FieldAttrs::parse_fields(&s.fields) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    // Generate local identifiers for each field
+    let fields: Vec<_> = s
+        .fields
+        .iter()
+        .zip(field_attrs)
+        .enumerate()
+        .map(|(i, (f, attrs))| {
+            let id = fields::binding(f, i);
+            (id, fields::member(f, i), f, attrs)
+        })
+        .collect();
+
+    // Map length fields to their targets, from either `length_of` or `length` attributes
+    let mut lengths = HashMap::new();
+    for (id, _m, _f, attrs) in &fields {
+        if let Some(t) = &attrs.length_of {
+            lengths.insert(id.to_string(), t.to_string());
+        }
+    }
+    for (id, _m, _f, attrs) in &fields {
+        if let Some(l) = &attrs.length {
+            lengths
+                .entry(l.to_string())
+                .or_insert_with(|| id.to_string());
+        }
+    }
+
+    // Generate values for normal fields first, then compute lengths from their targets
+    let mut generators = quote! {};
+    let mut computed = quote! {};
+    let mut values = quote! {};
+
+    for (id, member, f, _attrs) in &fields {
+        let ty = &f.ty;
+
+        match lengths.get(&id.to_string()) {
+            Some(target) => {
+                let target = Ident::new(target, ident.span());
+                computed.extend(quote! {
+                    let n = ::encdec::Encode::encode_len(&#target)
+                        .map_err(|_| ::encdec::arbitrary::Error::IncorrectFormat)?;
+                    let #id = <#ty as ::core::convert::TryFrom<usize>>::try_from(n)
+                        .map_err(|_| ::encdec::arbitrary::Error::IncorrectFormat)?;
+                })
+            }
+            None => generators.extend(quote! {
+                let #id = <#ty as ::encdec::arbitrary::Arbitrary>::arbitrary(u)?;
+            }),
+        }
+
+        values.extend(quote! { #member: #id, });
+    }
+
+    // Tuple fields are constructed by index (ie. `Self { 0: _0 }`)
+    let obj = quote!(Self { #values });
+
+    // Use the object lifetime for borrowed fields where available
+    let lifetimes: Vec<_> = generics.lifetimes().map(|v| v.lifetime.clone()).collect();
+    let lt = match lifetimes.first() {
+        Some(l) => l.clone(),
+        None => Lifetime::new("'arbitrary", ident.span()),
+    };
+    let impl_lt = match lifetimes.is_empty() {
+        true => Some(quote!(#lt,)),
+        false => None,
+    };
+
+    // Strip defaults from generic parameters
+    let params: Vec<_> = generics
+        .params
+        .iter()
+        .map(|p| match p.clone() {
+            GenericParam::Type(mut t) => {
+                t.eq_token = None;
+                t.default = None;
+                quote!(#t)
+            }
+            GenericParam::Const(mut c) => {
+                c.eq_token = None;
+                c.default = None;
+                quote!(#c)
+            }
+            GenericParam::Lifetime(l) => quote!(#l),
+        })
+        .collect();
+
+    let (_impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // Require arbitrary bounds on generic types
+    let mut where_bounds: Vec<_> = match where_clause {
+        Some(v) => v.predicates.iter().map(|v| quote!(#v)).collect(),
+        None => vec![],
+    };
+    for t in generics.type_params() {
+        let t = &t.ident;
+        where_bounds.push(quote!(#t: ::encdec::arbitrary::Arbitrary<#lt>));
+    }
+
+    quote! {
+        impl <#impl_lt #(#params),*> ::encdec::arbitrary::Arbitrary<#lt> for #ident #ty_generics where #(#where_bounds),* {
+            fn arbitrary(u: &mut ::encdec::arbitrary::Unstructured<#lt>) -> ::encdec::arbitrary::Result<Self> {
+                #generators
+
+                #computed
+
+                Ok(#obj)
+            }
+        }
+    }
+    .into()
+}
@@ -1,51 +1,80 @@
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 
-use darling::FromMeta;
 use quote::quote;
-use syn::{Attribute, Lit, Meta, NestedMeta};
+use syn::{spanned::Spanned, Attribute, Fields, Ident, Lit, Meta, NestedMeta};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct StructAttrs {
     /// Error type for derived methods
     pub error: Option<TokenStream>,
-}
 
-impl Default for StructAttrs {
-    fn default() -> Self {
-        Self { error: None }
-    }
+    /// Wrap field errors with struct / field path context
+    pub context: bool,
+
+    /// Reject trailing bytes following decoded objects
+    pub exact: bool,
+
+    /// Validation function called on decoded objects
+    pub validate: Option<TokenStream>,
+
+    /// Also call validation functions prior to encoding
+    pub validate_encode: bool,
+
+    /// Assert field encode lengths match encoded output in debug builds
+    pub check_len: bool,
+
+    /// Expected encoded length for fixed layouts, asserted at compile time
+    pub size: Option<TokenStream>,
+
+    /// Span of the `size` attribute, for reporting unsupported objects
+    pub size_span: Option<Span>,
 }
 
 impl StructAttrs {
     /// Parse [`StructAttrs`] object from field attributes
-    pub fn parse<'a>(attrs: impl Iterator<Item = &'a Attribute>) -> Self {
+    pub fn parse<'a>(attrs: impl Iterator<Item = &'a Attribute>) -> syn::Result<Self> {
         // Filter for `encdec` attribute group
         let attribute_args = attrs
             .filter_map(|v| v.parse_meta().ok())
             .find(|v| v.path().is_ident("encdec"))
-            .map(|v| match v {
+            .and_then(|v| match v {
                 Meta::List(l) => Some(l.nested),
                 _ => None,
-            })
-            .flatten();
+            });
 
         let attrs = match attribute_args {
             Some(a) => a,
-            None => return Default::default(),
+            None => return Ok(Default::default()),
         };
 
         let mut s = Self::default();
 
         // Parse attributes
         for a in attrs {
-            // Filter NameValue attributes
+            // Filter NameValue attributes, handling flags
             let v = match a {
                 NestedMeta::Meta(Meta::NameValue(v)) => v,
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("context") => {
+                    s.context = true;
+                    continue;
+                }
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("exact") => {
+                    s.exact = true;
+                    continue;
+                }
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("validate_encode") => {
+                    s.validate_encode = true;
+                    continue;
+                }
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("check_len") => {
+                    s.check_len = true;
+                    continue;
+                }
                 _ => continue,
             };
 
             // Process literal from value
-            let l = match lit_to_quote(&v.lit) {
+            let l = match lit_to_quote(&v.lit)? {
                 Some(l) => l,
                 None => continue,
             };
@@ -54,15 +83,72 @@ impl StructAttrs {
 
             // Lengths for tagged values
             if v.path.is_ident("error") {
-                s.error = Some(l.into());
+                s.error = Some(l);
+            } else if v.path.is_ident("validate") {
+                s.validate = Some(l);
+            } else if v.path.is_ident("size") {
+                s.size = Some(l);
+                s.size_span = Some(v.span());
             }
         }
 
-        s
+        Ok(s)
+    }
+
+    /// Fetch the error type for derived methods
+    pub fn error_type(&self) -> TokenStream {
+        match (&self.error, self.context) {
+            (Some(e), _) => quote!(#e),
+            (None, true) => quote!(::encdec::ContextError),
+            (None, false) => quote!(::encdec::Error),
+        }
+    }
+
+    /// Build an object level error from an [`encdec::Error`] expression,
+    /// setting the containing object when `context` is enabled
+    pub fn object_err(&self, object: &Ident, e: TokenStream) -> TokenStream {
+        let err = self.error_type();
+
+        match self.context {
+            true => quote!(#err::from(
+                ::encdec::ContextError::from(#e).in_object(stringify!(#object))
+            )),
+            false => quote!(#err::from(#e)),
+        }
+    }
+
+    /// Build error mapping for a field, applying field offsets to errors when using
+    /// the default error type or field paths when `context` is enabled
+    pub fn map_err(&self, object: &Ident, field: &str) -> TokenStream {
+        let err = self.error_type();
+
+        match (&self.error, self.context) {
+            (_, true) => quote!(.map_err(|e| #err::from(
+                ::encdec::ContextError::from(e)
+                    .offset_by(_index)
+                    .within(stringify!(#object), #field)
+            ))),
+            (Some(_), false) => quote!(),
+            (None, false) => quote!(.map_err(|e| ::encdec::Error::from(e).offset_by(_index))),
+        }
+    }
+
+    /// Build error mapping for a field without shifting offsets, for sink-based
+    /// encoders where errors report the sink position
+    pub fn map_context(&self, object: &Ident, field: &str) -> TokenStream {
+        let err = self.error_type();
+
+        match self.context {
+            true => quote!(.map_err(|e| #err::from(
+                ::encdec::ContextError::from(e)
+                    .within(stringify!(#object), #field)
+            ))),
+            false => quote!(),
+        }
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct FieldAttrs {
     /// Reference to length for decoding
     pub length: Option<TokenStream>,
@@ -81,70 +167,104 @@ pub struct FieldAttrs {
 
     /// Override encode/length/decode with methods from module
     pub with: Option<TokenStream>,
+
+    /// Override encoders write to a [`Sink`] with lengths counted, rather than a slice
+    pub sink: bool,
+
+    /// Validation function called on decoded fields
+    pub validate: Option<TokenStream>,
 }
 
 impl FieldAttrs {
-    /// Parse [`Attrs`] object from field attributes
-    pub fn parse<'a>(attrs: impl Iterator<Item = &'a Attribute>) -> Self {
+    /// Parse [`FieldAttrs`] object from field attributes
+    pub fn parse<'a>(attrs: impl Iterator<Item = &'a Attribute>) -> syn::Result<Self> {
         // Filter for `encdec` attribute group
         let attribute_args = attrs
             .filter_map(|v| v.parse_meta().ok())
             .find(|v| v.path().is_ident("encdec"))
-            .map(|v| match v {
+            .and_then(|v| match v {
                 Meta::List(l) => Some(l.nested),
                 _ => None,
-            })
-            .flatten();
+            });
 
         // Parse encdec attributes
         match attribute_args {
-            Some(a) => FieldAttrs::from(a.iter()),
-            None => FieldAttrs::default(),
+            Some(a) => FieldAttrs::from_nested(a.iter()),
+            None => Ok(FieldAttrs::default()),
         }
     }
-}
 
-impl Default for FieldAttrs {
-    fn default() -> Self {
-        Self {
-            length: None,
-            length_of: None,
-            encode: None,
-            encode_len: None,
-            decode: None,
-            with: None,
+    /// Parse [`FieldAttrs`] for each field in order
+    pub fn parse_fields(fields: &Fields) -> syn::Result<Vec<Self>> {
+        fields
+            .iter()
+            .map(|f| FieldAttrs::parse(f.attrs.iter()))
+            .collect()
+    }
+
+    /// Fetch the encode override for a field, from a `with` module or `enc` method
+    pub fn encoder(&self) -> Option<TokenStream> {
+        match (&self.with, &self.encode) {
+            (Some(m), _) => Some(quote! { #m::enc }),
+            (_, Some(e)) => Some(quote! { #e }),
+            _ => None,
+        }
+    }
+
+    /// Build the encoded length expression for a field
+    pub fn call_len(&self, id: &TokenStream) -> TokenStream {
+        match (&self.with, &self.encode_len, self.encoder()) {
+            // Sink encoder without length override, computed by counting encoded bytes
+            (_, None, Some(e)) if self.sink => {
+                quote! { ::encdec::encode::count_len(|s| #e(&self.#id, s))? }
+            }
+            // Slice encoder without length override, computed by encoding to scratch buffers
+            (None, None, Some(e)) => {
+                quote! { ::encdec::encode::scratch_len(|b| #e(&self.#id, b))? }
+            }
+            // Block / module override
+            (Some(m), _, _) if !self.sink => quote! { #m::enc_len(&self.#id)? },
+            // Encode length override
+            (_, Some(l), _) => quote! { #l(&self.#id)? },
+            // Default encode length method
+            (_, _, _) => quote! { self.#id.encode_len()? },
         }
     }
 }
 
-fn lit_to_quote(lit: &Lit) -> Option<TokenStream> {
+fn lit_to_quote(lit: &Lit) -> syn::Result<Option<TokenStream>> {
     match lit {
-        Lit::Int(v) => Some(quote! { #v }),
+        Lit::Int(v) => Ok(Some(quote! { #v })),
         Lit::Str(v) => {
-            let f = v.value();
-            let i = syn::Ident::from_string(&f).unwrap();
-            Some(quote! { #i })
+            // Parse as a path to support module / function paths and generic types,
+            // reporting invalid paths at the attribute value
+            let p = v.parse::<syn::Path>()?;
+            Ok(Some(quote! { #p }))
         }
-        Lit::Verbatim(v) => Some(quote! { #v }),
-        _ => None,
+        Lit::Verbatim(v) => Ok(Some(quote! { #v })),
+        _ => Ok(None),
     }
 }
 
-/// Create [`Attrs`] object from [`NestedMeta`] fields
-impl<'a, T: Iterator<Item = &'a NestedMeta>> From<T> for FieldAttrs {
-    fn from(args: T) -> Self {
+impl FieldAttrs {
+    /// Create [`FieldAttrs`] object from [`NestedMeta`] fields
+    fn from_nested<'a>(args: impl Iterator<Item = &'a NestedMeta>) -> syn::Result<Self> {
         let mut s = Self::default();
 
         // Iterate through field arguments
         for a in args {
-            // Filter NameValue attributes
+            // Filter NameValue attributes, handling flags
             let v = match a {
                 NestedMeta::Meta(Meta::NameValue(v)) => v,
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("sink") => {
+                    s.sink = true;
+                    continue;
+                }
                 _ => continue,
             };
 
             // Process literal from value
-            let l = match lit_to_quote(&v.lit) {
+            let l = match lit_to_quote(&v.lit)? {
                 Some(l) => l,
                 None => continue,
             };
@@ -153,23 +273,27 @@ impl<'a, T: Iterator<Item = &'a NestedMeta>> From<T> for FieldAttrs {
 
             // Lengths for tagged values
             if v.path.is_ident("length") {
-                s.length = Some(l.into());
+                s.length = Some(l);
             } else if v.path.is_ident("length_of") {
-                s.length_of = Some(l.into());
+                s.length_of = Some(l);
 
             // Encode / decode function overrides
             } else if v.path.is_ident("with") {
-                s.with = Some(l.into());
+                s.with = Some(l);
             } else if v.path.is_ident("enc") {
-                s.encode = Some(l.into());
+                s.encode = Some(l);
             } else if v.path.is_ident("enc_len") {
-                s.encode_len = Some(l.into());
+                s.encode_len = Some(l);
             } else if v.path.is_ident("dec") {
-                s.decode = Some(l.into());
+                s.decode = Some(l);
+
+            // Field validation
+            } else if v.path.is_ident("validate") {
+                s.validate = Some(l);
             }
         }
 
         // Return attribute object
-        s
+        Ok(s)
     }
 }
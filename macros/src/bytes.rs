@@ -0,0 +1,158 @@
+//! `#[derive(DecodeBytes)`] macro implementation
+
+use proc_macro::TokenStream;
+
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput};
+
+use crate::{
+    attrs::{FieldAttrs, StructAttrs},
+    fields,
+};
+
+/// DecodeBytes derive helper
+pub fn derive_decode_bytes_impl(input: TokenStream) -> TokenStream {
+    let DeriveInput {
+        ident,
+        data,
+        generics,
+        attrs,
+        ..
+    } = parse_macro_input!(input);
+
+    // Extract struct fields
+    let s = match data {
+        Data::Struct(s) => s,
+        _ => panic!("Unsupported object type for derivation"),
+    };
+
+    // Parse struct and field attributes, reporting invalid attributes at their span
+    let struct_attrs = match StructAttrs::parse(attrs.iter()) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let field_attrs = match FieldAttrs::parse_fields(&s.fields) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    // Build parser for each field
+    let mut parsers = quote! {};
+    let mut fields = quote! {};
+
+    // Fetch bounds for generics
+    let (impl_generics, ty_generics, _where_clause) = generics.split_for_impl();
+
+    s.fields.iter().enumerate().for_each(|(i, f)| {
+        let ty = &f.ty;
+
+        let id = fields::binding(f, i);
+        let member = fields::member(f, i);
+
+        let attrs = &field_attrs[i];
+
+        // Map field errors for offsets / context
+        let field = fields::name(f, i);
+        let map_err = struct_attrs.map_err(&ident, &field);
+
+        parsers.extend(quote! {
+            let _index = _pos;
+        });
+
+        match (&attrs.with, &attrs.decode, &attrs.length) {
+            // Block / module override, decoded from the underlying slice
+            (Some(m), _, _) => parsers.extend(quote! {
+                let (#id, _n) = #m::dec(&buff[_pos..])#map_err?;
+            }),
+            // Decode method override, decoded from the underlying slice
+            (_, Some(d), _) => parsers.extend(quote! {
+                let (#id, _n) = #d(&buff[_pos..])#map_err?;
+            }),
+            // Length delimited fields, decoded from a shared slice of the source
+            (_, _, Some(l)) => parsers.extend(quote! {
+                let _n = #l as usize;
+                let #id = <#ty as DecodeBytes>::decode_bytes_len(&buff.slice(_pos..), _n)#map_err?;
+            }),
+            // Normal fields, decoded from a shared slice of the remaining source
+            (_, _, None) => parsers.extend(quote! {
+                let (#id, _n) = <#ty as DecodeBytes>::decode_bytes(&buff.slice(_pos..))#map_err?;
+            }),
+        }
+
+        parsers.extend(quote! {
+            _pos += _n;
+        });
+
+        // Validate decoded fields, reporting errors at the field offset
+        if let Some(v) = &attrs.validate {
+            parsers.extend(quote! {
+                #v(&#id)#map_err?;
+            });
+        }
+
+        fields.extend(quote! { #member: #id, })
+    });
+
+    // Tuple fields are constructed by index (ie. `Self { 0: _0 }`)
+    let obj = quote!(Self { #fields });
+
+    // Override error return type if specified
+    let err = struct_attrs.error_type();
+
+    // Validate decoded objects
+    let validate = struct_attrs.validate.as_ref().map(|v| {
+        quote! {
+            #v(&_obj)?;
+        }
+    });
+
+    // Reject trailing bytes for exact objects
+    let exact = match struct_attrs.exact {
+        true => {
+            let e = struct_attrs.object_err(
+                &ident,
+                quote!(::encdec::Error::TrailingBytes {
+                    remaining: buff.len() - _pos,
+                    offset: _pos,
+                }),
+            );
+            Some(quote! {
+                if _pos < buff.len() {
+                    return Err(#e);
+                }
+            })
+        }
+        false => None,
+    };
+
+    // Add error bounds for DecodeBytes types
+    let where_clause = fields::where_clause(
+        &generics,
+        &["DecodeBytes"],
+        false,
+        |t, a| quote!(#err: From<<#t as #a>::Error>),
+    );
+
+    quote! {
+        impl #impl_generics ::encdec::decode::DecodeBytes for #ident #ty_generics #where_clause {
+            type Error = #err;
+
+            fn decode_bytes(buff: &::encdec::bytes::Bytes) -> Result<(Self, usize), Self::Error> {
+                use ::encdec::decode::DecodeBytes;
+
+                let mut _pos = 0;
+
+                #parsers
+
+                #exact
+
+                let _obj = #obj;
+
+                #validate
+
+                Ok((_obj, _pos))
+            }
+        }
+    }
+    .into()
+}
@@ -0,0 +1,138 @@
+//! `#[derive(EncodeChunked)`] macro implementation
+
+use proc_macro::TokenStream;
+
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput};
+
+use crate::{
+    attrs::{FieldAttrs, StructAttrs},
+    fields::{self, FieldEncoder},
+};
+
+/// EncodeChunked derive helper
+pub fn derive_encode_chunked_impl(input: TokenStream) -> TokenStream {
+    let DeriveInput {
+        ident,
+        data,
+        generics,
+        attrs,
+        ..
+    } = parse_macro_input!(input);
+
+    // Extract struct fields
+    let s = match data {
+        Data::Struct(s) => s,
+        _ => panic!("Unsupported object type for derivation"),
+    };
+
+    // Parse struct and field attributes, reporting invalid attributes at their span
+    let struct_attrs = match StructAttrs::parse(attrs.iter()) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let field_attrs = match FieldAttrs::parse_fields(&s.fields) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    // Fetch bounds for generics
+    let (impl_generics, ty_generics, _where_clause) = generics.split_for_impl();
+
+    // Build windowed encoder for each field
+    let mut encoders = quote! {};
+    let mut validators = quote! {};
+
+    s.fields.iter().enumerate().for_each(|(i, f)| {
+        let attrs = &field_attrs[i];
+        let id = fields::member(f, i);
+        let ty = &f.ty;
+
+        // Map field errors for offsets / context
+        let field = fields::name(f, i);
+        let map_err = struct_attrs.map_err(&ident, &field);
+
+        let call_len = attrs.call_len(&id);
+
+        let call_encode = match FieldEncoder::new(attrs, ty, &map_err) {
+            // Slice-based overrides, encoded via scratch buffer
+            FieldEncoder::Override(e) => quote! {
+                let _n = #call_len;
+                _w.field(_n, |o, b| {
+                    ::encdec::encode::encode_window(_n, o, b, |s| #e(&self.#id, s))#map_err
+                })?;
+            },
+            // Sink-based overrides, windowed while streaming
+            FieldEncoder::Sink(e) => quote! {
+                _w.field(#call_len, |o, b| {
+                    ::encdec::encode::encode_window_to(o, b, |s| #e(&self.#id, s))#map_err
+                })?;
+            },
+            // `length_of` types filled using length of target field
+            FieldEncoder::LengthOf(n) => quote! {
+                #n
+                _w.field(n.encode_len()?, |o, b| n.encode_range(o, b)#map_err)?;
+            },
+            // Normal fields using chunked encode
+            FieldEncoder::Field => quote! {
+                _w.field(#call_len, |o, b| self.#id.encode_range(o, b)#map_err)?;
+            },
+        };
+
+        // Validate fields prior to encoding where enabled
+        if let (Some(v), true) = (&attrs.validate, struct_attrs.validate_encode) {
+            validators.extend(quote! {
+                #v(&self.#id)#map_err?;
+            });
+        }
+
+        // Fields following a full window are not encoded (or measured)
+        encoders.extend(quote! {
+            if _w.is_full() {
+                return Ok(_w.finish());
+            }
+            let _index = _w.index();
+            #call_encode
+        });
+    });
+
+    // Validate objects prior to encoding where enabled
+    if let (Some(v), true) = (&struct_attrs.validate, struct_attrs.validate_encode) {
+        validators.extend(quote! {
+            #v(self)?;
+        });
+    }
+
+    // Override error return type if specified
+    let err = struct_attrs.error_type();
+
+    // Add chunked encode and error bounds for Encode types
+    let where_clause = fields::where_clause(
+        &generics,
+        &["Encode"],
+        true,
+        |t, a| quote!(#t: ::encdec::encode::EncodeChunked, #err: From<<#t as #a>::Error>),
+    );
+
+    quote! {
+        impl #impl_generics ::encdec::encode::EncodeChunked for #ident #ty_generics #where_clause {
+            fn encode_range(&self, offset: usize, buff: &mut [u8]) -> Result<usize, Self::Error> {
+                use ::encdec::Encode;
+                use ::encdec::encode::EncodeChunked;
+
+                // Validate once, prior to writing the first chunk
+                if offset == 0 {
+                    let _index = 0;
+                    #validators
+                }
+
+                let mut _w = ::encdec::encode::ChunkWindow::new(offset, buff);
+
+                #encoders
+
+                Ok(_w.finish())
+            }
+        }
+    }
+    .into()
+}
@@ -3,11 +3,12 @@
 use proc_macro::TokenStream;
 
 use quote::quote;
-use syn::{
-    parse::Parse, parse_macro_input, Data, DeriveInput, Fields, Ident, Lifetime, TypeParamBound,
-};
+use syn::{parse_macro_input, Data, DeriveInput};
 
-use crate::attrs::{FieldAttrs, StructAttrs};
+use crate::{
+    attrs::{FieldAttrs, StructAttrs},
+    fields,
+};
 
 /// Decode derive helper
 pub fn derive_decode_impl(input: TokenStream, owned: bool) -> TokenStream {
@@ -25,8 +26,15 @@ pub fn derive_decode_impl(input: TokenStream, owned: bool) -> TokenStream {
         _ => panic!("Unsupported object type for derivation"),
     };
 
-    // Parse struct attributes
-    let struct_attrs = StructAttrs::parse(attrs.iter());
+    // Parse struct and field attributes, reporting invalid attributes at their span
+    let struct_attrs = match StructAttrs::parse(attrs.iter()) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let field_attrs = match FieldAttrs::parse_fields(&s.fields) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error().into(),
+    };
 
     // Build parser for each field
     let mut parsers = quote! {};
@@ -38,48 +46,49 @@ pub fn derive_decode_impl(input: TokenStream, owned: bool) -> TokenStream {
     s.fields.iter().enumerate().for_each(|(i, f)| {
         let ty = &f.ty;
 
-        let id = match f.ident.clone() {
-            Some(id) => id,
-            None => Ident::new(&format!("_{}", i), ident.span()),
-        };
+        let id = fields::binding(f, i);
+        let member = fields::member(f, i);
+
+        let attrs = &field_attrs[i];
 
-        // Parse field attributes
-        let attrs = FieldAttrs::parse(f.attrs.iter());
+        // Map field errors for offsets / context
+        let field = fields::name(f, i);
+        let map_err = struct_attrs.map_err(&ident, &field);
+
+        parsers.extend(quote! {
+            let _index = _c.position();
+        });
 
         match (&attrs.with, &attrs.decode, &attrs.length) {
             (Some(m), _, _) => parsers.extend(quote! {
-                let (#id, n) = #m::dec(&buff[_index..])?;
-                _index += n;
+                let #id = _c.read_with(#m::dec)#map_err?;
             }),
             (_, Some(d), _) => parsers.extend(quote! {
-                let (#id, n) = #d(&buff[_index..])?;
-                _index += n;
+                let #id = _c.read_with(#d)#map_err?;
             }),
             (_, _, Some(l)) => parsers.extend(quote! {
-                let n = #l as usize;
-                let #id = <#ty>::decode_len(&buff[_index..], n)?;
-                _index += n;
+                let #id = _c.read_len_limited::<#ty>(#l as usize, _limits)#map_err?;
             }),
             (_, _, None) => parsers.extend(quote! {
-                let (#id, n) = <#ty>::decode(&buff[_index..])?;
+                let #id = _c.read_limited::<#ty>(_limits)#map_err?;
                 let #id = #id.into();
-                _index += n;
             }),
         }
 
-        fields.extend(quote! { #id, })
+        // Validate decoded fields, reporting errors at the field offset
+        if let Some(v) = &attrs.validate {
+            parsers.extend(quote! {
+                #v(&#id)#map_err?;
+            });
+        }
+
+        fields.extend(quote! { #member: #id, })
     });
 
-    let obj = match s.fields {
-        Fields::Named(_) => quote!(Self{#fields}),
-        Fields::Unnamed(_) => quote!(Self(#fields)),
-        Fields::Unit => quote!(Self{#fields}),
-    };
+    // Tuple fields are constructed by index (ie. `Self { 0: _0 }`)
+    let obj = quote!(Self { #fields });
 
-    let lifetimes: Vec<_> = generics
-        .lifetimes()
-        .map(|v| Lifetime::from(v.lifetime.clone()))
-        .collect();
+    let lifetimes: Vec<_> = generics.lifetimes().map(|v| v.lifetime.clone()).collect();
 
     let generic_types: Vec<_> = generics.type_params().collect();
 
@@ -94,63 +103,41 @@ pub fn derive_decode_impl(input: TokenStream, owned: bool) -> TokenStream {
         .collect();
 
     // Override error return type if specified
-    let err = match struct_attrs.error {
-        Some(e) => quote!(#e),
-        None => quote!(::encdec::Error),
-    };
+    let err = struct_attrs.error_type();
 
-    // Extract where bounds
-    let mut where_bounds = match &generics.where_clause {
-        Some(v) => v.predicates.iter().map(|v| quote!(#v)).collect(),
-        _ => vec![],
-    };
+    // Validate decoded objects
+    let validate = struct_attrs.validate.as_ref().map(|v| {
+        quote! {
+            #v(&_obj)?;
+        }
+    });
 
-    // Add where bounds for Decode types
-    for g in &generic_types {
-        // Look for types with Decode bounds
-        let a = g.bounds.iter().find_map(|v| {
-            // Find trait bounds
-            let t = match v {
-                TypeParamBound::Trait(t) => t,
-                _ => return None,
-            };
-
-            // Match decode bounds
-            let _s = match t.path.segments.first() {
-                Some(v) if v.ident == "Decode" => v,
-                Some(v) if v.ident == "DecodeOwned" => v,
-                _ => return None,
-            };
-
-            Some(v)
-        });
+    // Reject trailing bytes for exact objects
+    let exact = match struct_attrs.exact {
+        true => {
+            let e = struct_attrs.object_err(
+                &ident,
+                quote!(::encdec::Error::TrailingBytes {
+                    remaining: _c.remaining(),
+                    offset: _c.position(),
+                }),
+            );
+            Some(quote! {
+                if !_c.is_empty() {
+                    return Err(#e);
+                }
+            })
+        }
+        false => None,
+    };
 
-        // Skip non-Decode types (probably not possible?)
-        let a = match a {
-            Some(v) => v,
-            None => continue,
-        };
-
-        // Fetch type
-        let t = &g.ident;
-
-        // Append where clause
-        let w = quote!(
-            #t: From<<#t as #a>::Output>,
-            #err: From<<#t as #a>::Error>,
-        );
-
-        where_bounds.push(w);
-    }
-
-    // Build where clause
-    let mut where_clause = None;
-    if where_bounds.len() > 0 {
-        where_clause = Some(quote! {
-            where
-                #(#where_bounds),*
-        });
-    }
+    // Add output conversion and error bounds for Decode types
+    let where_clause = fields::where_clause(
+        &generics,
+        &["Decode", "DecodeOwned"],
+        true,
+        |t, a| quote!(#t: From<<#t as #a>::Output>, #err: From<<#t as #a>::Error>),
+    );
 
     //panic!("bounds: {}", TokenStream::from(where_clause.unwrap()));
 
@@ -161,13 +148,30 @@ pub fn derive_decode_impl(input: TokenStream, owned: bool) -> TokenStream {
                 type Error = #err;
                 
                 fn decode(buff: &'dec [u8]) -> Result<(Self::Output, usize), Self::Error> {
+                    Self::decode_limited(buff, &mut ::encdec::decode::DecodeLimits::new())
+                }
+
+                fn decode_limited(buff: &'dec [u8], _limits: &mut ::encdec::decode::DecodeLimits) -> Result<(Self::Output, usize), Self::Error> {
                     use ::encdec::decode::{Decode, DecodedTagged, DecodePrefixed};
 
-                    let mut _index = 0;
-                    
+                    // Enter a nested object, exiting on drop where decoding fails
+                    let mut _scope = _limits.scope()?;
+                    let _limits = &mut *_scope;
+
+                    let mut _c = ::encdec::decode::DecodeCursor::new(buff);
+
                     #parsers
 
-                    Ok((#obj, _index))
+                    drop(_scope);
+
+                    // Check trailing bytes once the nested object has been exited
+                    #exact
+
+                    let _obj = #obj;
+
+                    #validate
+
+                    Ok((_obj, _c.position()))
                 }
             }
         },
@@ -177,13 +181,30 @@ pub fn derive_decode_impl(input: TokenStream, owned: bool) -> TokenStream {
                 type Error = #err;
                 
                 fn decode_owned(buff: &[u8]) -> Result<(Self::Output, usize), Self::Error> {
-                    use ::encdec::decode::{DecodeOwned};
+                    Self::decode_owned_limited(buff, &mut ::encdec::decode::DecodeLimits::new())
+                }
+
+                fn decode_owned_limited(buff: &[u8], _limits: &mut ::encdec::decode::DecodeLimits) -> Result<(Self::Output, usize), Self::Error> {
+                    use ::encdec::decode::{Decode, DecodeOwned, DecodedTagged};
+
+                    // Enter a nested object, exiting on drop where decoding fails
+                    let mut _scope = _limits.scope()?;
+                    let _limits = &mut *_scope;
+
+                    let mut _c = ::encdec::decode::DecodeCursor::new(buff);
 
-                    let mut _index = 0;
-                    
                     #parsers
 
-                    Ok((#obj, _index))
+                    drop(_scope);
+
+                    // Check trailing bytes once the nested object has been exited
+                    #exact
+
+                    let _obj = #obj;
+
+                    #validate
+
+                    Ok((_obj, _c.position()))
                 }
             }
         },
@@ -3,9 +3,12 @@
 use proc_macro::TokenStream;
 
 use quote::quote;
-use syn::{parse_macro_input, Data, DeriveInput, TypeParamBound};
+use syn::{parse_macro_input, Data, DeriveInput};
 
-use crate::attrs::{FieldAttrs, StructAttrs};
+use crate::{
+    attrs::{FieldAttrs, StructAttrs},
+    fields::{self, FieldEncoder},
+};
 
 /// Encode derive helper
 pub fn derive_encode_impl(input: TokenStream) -> TokenStream {
@@ -23,8 +26,15 @@ pub fn derive_encode_impl(input: TokenStream) -> TokenStream {
         _ => panic!("Unsupported object type for derivation"),
     };
 
-    // Parse struct attributes
-    let struct_attrs = StructAttrs::parse(attrs.iter());
+    // Parse struct and field attributes, reporting invalid attributes at their span
+    let struct_attrs = match StructAttrs::parse(attrs.iter()) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let field_attrs = match FieldAttrs::parse_fields(&s.fields) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error().into(),
+    };
 
     // Fetch bounds for generics
     let (impl_generics, ty_generics, _where_clause) = generics.split_for_impl();
@@ -32,104 +42,167 @@ pub fn derive_encode_impl(input: TokenStream) -> TokenStream {
     // Build parser for each field
     let mut encoders = quote! {};
     let mut lengths = quote! {};
+    let mut validators = quote! {};
 
-    s.fields.iter().enumerate().for_each(|(i, f)| {
-        // Parse field attributes
-        let attrs = FieldAttrs::parse(f.attrs.iter());
-
-        // Generate field identifier
-        let id = match f.ident.clone() {
-            Some(id) => quote! { #id },
-            None => {
-                let id = syn::Index::from(i);
-                quote! { #id }
-            }
-        };
+    // Build compile-time length bounds, available where no field overrides encoding
+    let mut max_lens = vec![];
+    let mut fixed_lens = vec![];
+    let mut max_bounds = vec![];
+    let mut fixed_bounds = vec![];
+    let mut bounded = true;
 
+    s.fields.iter().enumerate().for_each(|(i, f)| {
+        let attrs = &field_attrs[i];
+        let id = fields::member(f, i);
         let ty = &f.ty;
 
-        let call_encode = match (&attrs.with, &attrs.encode, &attrs.length_of) {
-            // Block / module override
-            (Some(m), _, _) => quote! {
-                _index += #m::enc(&self.#id, &mut buff[_index..])?;
-            },
-            // Encode method override
-            (_, Some(e), _) => quote! {
-                _index += #e(&self.#id, &mut buff[_index..])?;
+        // Map field errors for offsets / context
+        let field = fields::name(f, i);
+        let map_err = struct_attrs.map_err(&ident, &field);
+
+        let call_encode = match FieldEncoder::new(attrs, ty, &map_err) {
+            // Block / module or encode method override
+            FieldEncoder::Override(e) => quote! {
+                _c.write_with(|b| #e(&self.#id, b))#map_err?;
             },
-            // Normal fields using normal encode
-            (_, _, None) => quote! {
-                _index += self.#id.encode(&mut buff[_index..])?;
+            // Sink-based override, writing through the cursor
+            FieldEncoder::Sink(e) => quote! {
+                #e(&self.#id, &mut _c)#map_err?;
             },
             // `length_of` types filled using length of target field
-            (_, _, Some(v)) => quote! {
-                let n = self.#v.encode_len()?;
-                _index += (n as #ty).encode(&mut buff[_index..])?;
+            FieldEncoder::LengthOf(n) => quote! {
+                #n
+                _c.write(&n)#map_err?;
+            },
+            // Normal fields, with lengths checked by the outermost object
+            FieldEncoder::Field => quote! {
+                _c.write_nested(&self.#id)#map_err?;
             },
         };
 
-        let call_len = match (&attrs.with, &attrs.encode_len) {
-            // Block / module override
-            (Some(m), _) => quote! { _index += #m::enc_len(&self.#id)?; },
-            // Encode length override
-            (_, Some(l)) => quote! { _index += #l(&self.#id)?; },
-            // Default encode length method
-            (_, _) => quote! { _index += self.#id.encode_len()?; },
-        };
+        let call_len = attrs.call_len(&id);
+
+        // Validate fields prior to encoding where enabled
+        if let (Some(v), true) = (&attrs.validate, struct_attrs.validate_encode) {
+            validators.extend(quote! {
+                #v(&self.#id)#map_err?;
+            });
+        }
+
+        // Check encoded field lengths in debug builds where enabled
+        let check_len = struct_attrs.check_len.then(|| {
+            quote! {
+                debug_assert_eq!(
+                    _c.position() - _index, #call_len,
+                    concat!(stringify!(#ident), ".", #field, " encode_len does not match encoded length")
+                );
+            }
+        });
 
-        encoders.extend(call_encode);
-        lengths.extend(call_len);
+        encoders.extend(quote! {
+            let _index = _c.position();
+            #call_encode
+            #check_len
+        });
+        lengths.extend(quote! { _index += #call_len; });
+
+        // Field types bound the encoded length unless encoding is overridden,
+        // with bounds on concrete types deferred so unbounded fields omit the impl
+        match (&attrs.with, &attrs.encode) {
+            (None, None) => {
+                max_lens.push(quote!(<#ty as ::encdec::encode::MaxEncodedLen>::MAX_ENCODED_LEN));
+                fixed_lens.push(quote!(<#ty as ::encdec::encode::FixedEncodedLen>::ENCODED_LEN));
+                max_bounds.push(quote!(for<'__encdec> #ty: ::encdec::encode::MaxEncodedLen));
+                fixed_bounds.push(quote!(for<'__encdec> #ty: ::encdec::encode::FixedEncodedLen));
+            }
+            _ => bounded = false,
+        }
     });
 
+    // Validate objects prior to encoding where enabled
+    if let (Some(v), true) = (&struct_attrs.validate, struct_attrs.validate_encode) {
+        validators.extend(quote! {
+            #v(self)?;
+        });
+    }
+
     // Override error return type if specified
-    let err = match struct_attrs.error {
-        Some(e) => quote!(#e),
-        None => quote!(::encdec::Error),
-    };
+    let err = struct_attrs.error_type();
+
+    // Error for undersized buffers, checked prior to writing
+    let too_small = struct_attrs.object_err(
+        &ident,
+        quote!(::encdec::Error::BufferTooSmall {
+            needed: _len,
+            available: buff.len(),
+            offset: 0,
+        }),
+    );
 
-    // Setup where bounds on generic types
+    // Add error bounds for Encode types
+    let where_clause = fields::where_clause(
+        &generics,
+        &["Encode"],
+        true,
+        |t, a| quote!(#err: From<<#t as #a>::Error>),
+    );
+
+    // Implement compile-time length bounds where all fields are bounded
+    let mut len_impls = quote! {};
+    if bounded {
+        // Retain existing predicates, skipping derived `Encode` bounds
+        let predicates: Vec<_> = match &generics.where_clause {
+            Some(v) => v.predicates.iter().map(|v| quote!(#v)).collect(),
+            _ => vec![],
+        };
 
-    // Extract existing predicates
-    let mut where_bounds = match &generics.where_clause {
-        Some(v) => v.predicates.iter().map(|v| quote!(#v)).collect(),
-        _ => vec![],
-    };
+        len_impls.extend(quote! {
+            impl #impl_generics ::encdec::encode::MaxEncodedLen for #ident #ty_generics
+            where
+                #(#predicates,)*
+                #(#max_bounds,)*
+            {
+                const MAX_ENCODED_LEN: usize = 0 #(+ #max_lens)*;
+            }
 
-    // Add error bounds for Encode types
-    for g in generics.type_params() {
-        // Look for types with Decode bounds
-        let a = g.bounds.iter().find_map(|v| match v {
-            TypeParamBound::Trait(t) if t.path.is_ident("Encode") => Some(t),
-            _ => return None,
+            impl #impl_generics ::encdec::encode::FixedEncodedLen for #ident #ty_generics
+            where
+                #(#predicates,)*
+                #(#fixed_bounds,)*
+            {
+                const ENCODED_LEN: usize = 0 #(+ #fixed_lens)*;
+            }
         });
+    }
 
-        // Skip non-Decode types (probably not possible?)
-        let a = match a {
-            Some(v) => v,
-            None => continue,
+    // Assert fixed layouts match the expected size
+    if let (Some(size), Some(span)) = (&struct_attrs.size, struct_attrs.size_span) {
+        let e = if !bounded {
+            Some("`size` attribute requires fields without encode overrides")
+        } else if generics.type_params().next().is_some()
+            || generics.const_params().next().is_some()
+        {
+            Some("`size` attribute is not supported for generic objects")
+        } else {
+            None
         };
+        if let Some(e) = e {
+            return syn::Error::new(span, e).to_compile_error().into();
+        }
 
-        // Fetch type
-        let t = &g.ident;
-
-        // Append where clause
-        let w = quote!(
-            #err: From<<#t as #a>::Error>,
-        );
-
-        where_bounds.push(w);
-    }
+        let lifetimes = generics.lifetimes().map(|_| quote!('static));
 
-    // Build where clause
-    let mut where_clause = None;
-    if where_bounds.len() > 0 {
-        where_clause = Some(quote! {
-            where
-                #(#where_bounds),*
+        len_impls.extend(quote! {
+            const _: () = assert!(
+                <#ident<#(#lifetimes),*> as ::encdec::encode::FixedEncodedLen>::ENCODED_LEN == #size,
+                concat!(stringify!(#ident), " encoded length does not match `size` attribute")
+            );
         });
     }
 
     quote! {
+        #len_impls
+
         impl #impl_generics ::encdec::Encode for #ident #ty_generics #where_clause {
 
             type Error = #err;
@@ -145,13 +218,27 @@ pub fn derive_encode_impl(input: TokenStream) -> TokenStream {
             }
 
             fn encode(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
+                // Check the object fits prior to writing any fields
+                let _len = self.encode_len()?;
+                if buff.len() < _len {
+                    return Err(#too_small);
+                }
+
+                self.encode_nested(buff)
+            }
+
+            fn encode_nested(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
                 use ::encdec::Encode;
 
-                let mut _index = 0;
+                let _index = 0;
+
+                #validators
+
+                let mut _c = ::encdec::encode::EncodeCursor::new(buff);
 
                 #encoders
 
-                Ok(_index)
+                Ok(_c.position())
             }
         }
     }
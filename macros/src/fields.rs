@@ -0,0 +1,141 @@
+//! Field dispatch and generic bound helpers shared by derive implementations
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    spanned::Spanned, Field, GenericArgument, Generics, Ident, PathArguments, TraitBound, Type,
+    TypeParamBound,
+};
+
+use crate::attrs::FieldAttrs;
+
+/// Fetch the member used to access a field (ie. `self.#member`)
+pub fn member(f: &Field, i: usize) -> TokenStream {
+    match &f.ident {
+        Some(id) => quote! { #id },
+        None => {
+            let id = syn::Index::from(i);
+            quote! { #id }
+        }
+    }
+}
+
+/// Fetch the local binding used for a decoded field, `_N` for tuple fields
+pub fn binding(f: &Field, i: usize) -> Ident {
+    match &f.ident {
+        Some(id) => id.clone(),
+        None => Ident::new(&format!("_{}", i), f.span()),
+    }
+}
+
+/// Fetch the field name reported in error contexts
+pub fn name(f: &Field, i: usize) -> String {
+    match &f.ident {
+        Some(id) => id.to_string(),
+        None => i.to_string(),
+    }
+}
+
+/// Encoder selected for a field by its attributes
+pub enum FieldEncoder {
+    /// Slice-based `with` module or `enc` override, called as `#f(&self.#id, buff)`
+    Override(TokenStream),
+    /// Sink-based `with` module or `enc` override, called as `#f(&self.#id, sink)`
+    Sink(TokenStream),
+    /// `length_of` field, binding the length of the target field as `n`
+    LengthOf(TokenStream),
+    /// Field encoded using the derived trait
+    Field,
+}
+
+impl FieldEncoder {
+    /// Select the encoder for a field, mapping `length_of` conversion errors with `map_err`
+    pub fn new(attrs: &FieldAttrs, ty: &Type, map_err: &TokenStream) -> Self {
+        match (attrs.encoder(), &attrs.length_of) {
+            (Some(e), _) if attrs.sink => Self::Sink(e),
+            (Some(e), _) => Self::Override(e),
+            (_, Some(v)) => Self::LengthOf(quote! {
+                let n = self.#v.encode_len()?;
+                let n = <#ty as ::core::convert::TryFrom<usize>>::try_from(n)
+                    .map_err(|_| ::encdec::Error::Overflow { offset: 0 })#map_err?;
+            }),
+            (_, None) => Self::Field,
+        }
+    }
+}
+
+/// Build the where clause for a derived impl
+///
+/// Existing predicates are retained, `bounds` is called for each type parameter bound
+/// by one of `traits` (ie. `T: Encode` or `T: encdec::Encode<Error = Error>`) to add field
+/// bounds, and `Self: Debug` is required for generic objects where `debug` is set,
+/// matching `Encode` / `Decode`.
+pub fn where_clause(
+    generics: &Generics,
+    traits: &[&str],
+    debug: bool,
+    bounds: impl Fn(&Ident, &TraitBound) -> TokenStream,
+) -> Option<TokenStream> {
+    // Extract existing predicates
+    let mut where_bounds: Vec<_> = match &generics.where_clause {
+        Some(v) => v.predicates.iter().map(|v| quote!(#v)).collect(),
+        _ => vec![],
+    };
+
+    for g in generics.type_params() {
+        // Look for types bound by the derived traits
+        let a = g.bounds.iter().find_map(|v| match v {
+            TypeParamBound::Trait(t)
+                if matches!(t.path.segments.last(), Some(s) if traits.iter().any(|v| s.ident == v)) =>
+            {
+                Some(t)
+            }
+            _ => None,
+        });
+
+        if let Some(a) = a {
+            where_bounds.push(bounds(&g.ident, &trait_path(a)));
+        }
+    }
+
+    if debug && generics.type_params().next().is_some() {
+        where_bounds.insert(0, quote!(Self: ::core::fmt::Debug));
+    }
+
+    if where_bounds.is_empty() {
+        return None;
+    }
+
+    Some(quote! {
+        where
+            #(#where_bounds),*
+    })
+}
+
+/// Strip associated type constraints (ie. `Encode<Error = Error>`) from a trait bound,
+/// retaining lifetimes so the bound may be used as a qualified path (`<T as Decode<'a>>`)
+fn trait_path(t: &TraitBound) -> TraitBound {
+    let mut t = t.clone();
+
+    if let Some(PathArguments::AngleBracketed(a)) =
+        t.path.segments.last_mut().map(|s| &mut s.arguments)
+    {
+        a.args = a
+            .args
+            .iter()
+            .filter(|v| {
+                !matches!(
+                    v,
+                    GenericArgument::Binding(_) | GenericArgument::Constraint(_)
+                )
+            })
+            .cloned()
+            .collect();
+
+        if a.args.is_empty() {
+            t.path.segments.last_mut().unwrap().arguments = PathArguments::None;
+        }
+    }
+
+    t
+}
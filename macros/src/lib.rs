@@ -4,9 +4,16 @@
 extern crate proc_macro;
 use proc_macro::TokenStream;
 
+mod arbitrary;
 mod attrs;
+mod bytes;
+mod chunked;
 mod decode;
 mod encode;
+mod fields;
+mod sink;
+mod split;
+mod vectored;
 
 /// `#[derive(Encode)]` support.
 ///
@@ -46,11 +53,57 @@ mod encode;
 ///   }
 /// }
 /// ```
+///
+/// Where all fields implement [`MaxEncodedLen`][encdec_base::encode::MaxEncodedLen] /
+/// [`FixedEncodedLen`][encdec_base::encode::FixedEncodedLen] (and no `with` / `enc` overrides
+/// are used) these traits are also implemented, with `#[encdec(size = N)]` asserting the
+/// encoded length of fixed layouts at compile time.
 #[proc_macro_derive(Encode, attributes(encdec))]
 pub fn derive_encode_impl(input: TokenStream) -> TokenStream {
     encode::derive_encode_impl(input)
 }
 
+/// `#[derive(EncodeChunked)]` support.
+///
+/// generates an [`EncodeChunked`][encdec_base::encode::EncodeChunked] implementation writing the window
+/// of each field overlapping the requested range, for objects also deriving [`Encode`][encdec_base::encode::Encode].
+///
+/// Fields are encoded with `.encode_range()` so must also implement `EncodeChunked`,
+/// other than those using `with` or `enc` overrides which are encoded via a scratch buffer
+/// (see [`encode_window`][encdec_base::encode::encode_window]), limited to
+/// [`CHUNK_SCRATCH`][encdec_base::encode::CHUNK_SCRATCH] bytes without the `alloc` feature,
+/// or streamed directly where the `sink` flag is set.
+#[proc_macro_derive(EncodeChunked, attributes(encdec))]
+pub fn derive_encode_chunked_impl(input: TokenStream) -> TokenStream {
+    chunked::derive_encode_chunked_impl(input)
+}
+
+/// `#[derive(EncodeSink)]` support.
+///
+/// generates an [`EncodeSink`][encdec_base::encode::EncodeSink] implementation writing each field
+/// to the provided sink in order, for objects also deriving [`Encode`][encdec_base::encode::Encode].
+///
+/// Fields are encoded with `.encode_to()` so must also implement `EncodeSink`,
+/// other than those using `with` or `enc` overrides which are encoded via a scratch buffer
+/// (see [`encode_scratch`][encdec_base::encode::encode_scratch]) or streamed directly
+/// where the `sink` flag is set.
+#[proc_macro_derive(EncodeSink, attributes(encdec))]
+pub fn derive_encode_sink_impl(input: TokenStream) -> TokenStream {
+    sink::derive_encode_sink_impl(input)
+}
+
+/// `#[derive(EncodeVectored)]` support.
+///
+/// generates an [`EncodeVectored`][encdec_base::encode::EncodeVectored] implementation appending each field
+/// to the provided vectored output in order, for objects also deriving [`Encode`][encdec_base::encode::Encode].
+///
+/// Fields are encoded with `.encode_vectored()` so must also implement `EncodeVectored`,
+/// other than those using `with` or `enc` overrides which are encoded into the scratch buffer.
+#[proc_macro_derive(EncodeVectored, attributes(encdec))]
+pub fn derive_encode_vectored_impl(input: TokenStream) -> TokenStream {
+    vectored::derive_encode_vectored_impl(input)
+}
+
 /// `#[derive(Decode)]` support.
 ///
 /// generates a [`Decode`][encdec_base::decode::Decode] implementation equivalent to calling `.decode()` on each field in order.
@@ -132,3 +185,45 @@ pub fn derive_decode_borrowed_impl(input: TokenStream) -> TokenStream {
 pub fn derive_decode_owned_impl(input: TokenStream) -> TokenStream {
     decode::derive_decode_impl(input, true)
 }
+
+/// `#[derive(DecodeSplit)]` support.
+///
+/// generates a [`DecodeSplit`][encdec_base::decode::DecodeSplit] implementation decoding each field
+/// in order from non-contiguous input, for objects also deriving `Decode` or `DecodeOwned`.
+///
+/// Fields are decoded with `.decode_split()` so must also implement `DecodeSplit`, other than
+/// `length` delimited fields which are borrowed via scratch space where spanning the split, and
+/// those using `with` or `dec` overrides which are decoded from the remaining input, copied
+/// via a stack buffer or scratch space where spanning the split
+/// (see [`decode_scratch`][encdec_base::decode::decode_scratch]).
+#[proc_macro_derive(DecodeSplit, attributes(encdec))]
+pub fn derive_decode_split_impl(input: TokenStream) -> TokenStream {
+    split::derive_decode_split_impl(input)
+}
+
+/// `#[derive(DecodeBytes)]` support.
+///
+/// generates a [`DecodeBytes`][encdec_base::decode::DecodeBytes] implementation decoding each field
+/// in order from a shared `bytes::Bytes` buffer, with `Bytes` fields returned as reference counted
+/// slices of the source rather than copied.
+///
+/// Fields are decoded with `.decode_bytes()` (implemented for all `DecodeOwned` types), with
+/// `length` delimited fields decoded from a slice of the specified length.
+///
+/// Requires the `bytes` feature on `encdec`.
+#[proc_macro_derive(DecodeBytes, attributes(encdec))]
+pub fn derive_decode_bytes_impl(input: TokenStream) -> TokenStream {
+    bytes::derive_decode_bytes_impl(input)
+}
+
+/// `#[derive(Arbitrary)]` support.
+///
+/// generates an [`arbitrary::Arbitrary`](https://docs.rs/arbitrary) implementation consistent
+/// with `encdec` field attributes, computing `length_of` / `length` fields from the encoded
+/// length of their target fields so generated objects are always encodable.
+///
+/// Requires the `arbitrary` feature on `encdec`.
+#[proc_macro_derive(Arbitrary, attributes(encdec))]
+pub fn derive_arbitrary_impl(input: TokenStream) -> TokenStream {
+    arbitrary::derive_arbitrary_impl(input)
+}
@@ -0,0 +1,192 @@
+//! `#[derive(DecodeSplit)`] macro implementation
+
+use proc_macro::TokenStream;
+
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput};
+
+use crate::{
+    attrs::{FieldAttrs, StructAttrs},
+    fields,
+};
+
+/// DecodeSplit derive helper
+pub fn derive_decode_split_impl(input: TokenStream) -> TokenStream {
+    let DeriveInput {
+        ident,
+        data,
+        generics,
+        attrs,
+        ..
+    } = parse_macro_input!(input);
+
+    // Extract struct fields
+    let s = match data {
+        Data::Struct(s) => s,
+        _ => panic!("Unsupported object type for derivation"),
+    };
+
+    // Parse struct and field attributes, reporting invalid attributes at their span
+    let struct_attrs = match StructAttrs::parse(attrs.iter()) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let field_attrs = match FieldAttrs::parse_fields(&s.fields) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    // Build split parser for each field
+    let mut parsers = quote! {};
+    let mut fields = quote! {};
+
+    // Fetch bounds for generics
+    let (_impl_generics, ty_generics, _where_clause) = generics.split_for_impl();
+
+    s.fields.iter().enumerate().for_each(|(i, f)| {
+        let ty = &f.ty;
+
+        let id = fields::binding(f, i);
+        let member = fields::member(f, i);
+
+        let attrs = &field_attrs[i];
+
+        // Map field errors for offsets / context
+        let field = fields::name(f, i);
+        let map_err = struct_attrs.map_err(&ident, &field);
+
+        parsers.extend(quote! {
+            let _index = _pos;
+            let _b = buff.advance(_index);
+        });
+
+        match (&attrs.with, &attrs.decode, &attrs.length) {
+            // Block / module override, copied to contiguous memory where split
+            (Some(m), _, _) => parsers.extend(quote! {
+                let (#id, _n) = ::encdec::decode::decode_scratch(_b, scratch, #m::dec)#map_err?;
+            }),
+            // Decode method override, copied to contiguous memory where split
+            (_, Some(d), _) => parsers.extend(quote! {
+                let (#id, _n) = ::encdec::decode::decode_scratch(_b, scratch, #d)#map_err?;
+            }),
+            // Length delimited fields, borrowed via scratch space where split
+            (_, _, Some(l)) => parsers.extend(quote! {
+                let _n = #l as usize;
+                let _d = scratch.contiguous(_b, _n)#map_err?;
+                let #id = <#ty as DecodedTagged>::decode_len_limited(_d, _n, _limits)#map_err?;
+            }),
+            // Normal fields using split decode
+            (_, _, None) => parsers.extend(quote! {
+                let (#id, _n) = <#ty as DecodeSplit>::decode_split_limited(_b, scratch, _limits)#map_err?;
+                let #id = #id.into();
+            }),
+        }
+
+        parsers.extend(quote! {
+            _pos += _n;
+        });
+
+        // Validate decoded fields, reporting errors at the field offset
+        if let Some(v) = &attrs.validate {
+            parsers.extend(quote! {
+                #v(&#id)#map_err?;
+            });
+        }
+
+        fields.extend(quote! { #member: #id, })
+    });
+
+    // Tuple fields are constructed by index (ie. `Self { 0: _0 }`)
+    let obj = quote!(Self { #fields });
+
+    let lifetimes: Vec<_> = generics.lifetimes().map(|v| v.lifetime.clone()).collect();
+
+    let generic_types: Vec<_> = generics.type_params().collect();
+
+    let const_params: Vec<_> = generics
+        .const_params()
+        .map(|v| {
+            let mut v = v.clone();
+            v.eq_token = None;
+            v.default = None;
+            v
+        })
+        .collect();
+
+    // Override error return type if specified
+    let err = struct_attrs.error_type();
+
+    // Validate decoded objects
+    let validate = struct_attrs.validate.as_ref().map(|v| {
+        quote! {
+            #v(&_obj)?;
+        }
+    });
+
+    // Reject trailing bytes for exact objects
+    let exact = match struct_attrs.exact {
+        true => {
+            let e = struct_attrs.object_err(
+                &ident,
+                quote!(::encdec::Error::TrailingBytes {
+                    remaining: buff.len() - _pos,
+                    offset: _pos,
+                }),
+            );
+            Some(quote! {
+                if _pos < buff.len() {
+                    return Err(#e);
+                }
+            })
+        }
+        false => None,
+    };
+
+    // Add split decode and error bounds for Decode types
+    let where_clause = fields::where_clause(&generics, &["Decode", "DecodeOwned"], true, |t, a| {
+        quote!(
+            #t: ::encdec::decode::DecodeSplit<'dec>,
+            #t: From<<#t as #a>::Output>,
+            #err: From<<#t as #a>::Error>
+        )
+    });
+
+    quote! {
+        impl <'dec: #(#lifetimes)+*, #(#lifetimes),* #(#generic_types),* #(#const_params),*> ::encdec::decode::DecodeSplit<'dec> for #ident #ty_generics #where_clause {
+            fn decode_split(
+                buff: ::encdec::decode::SplitBuf<'dec>,
+                scratch: &mut ::encdec::decode::Scratch<'dec>,
+            ) -> Result<(Self::Output, usize), Self::Error> {
+                Self::decode_split_limited(buff, scratch, &mut ::encdec::decode::DecodeLimits::new())
+            }
+
+            fn decode_split_limited(
+                buff: ::encdec::decode::SplitBuf<'dec>,
+                scratch: &mut ::encdec::decode::Scratch<'dec>,
+                _limits: &mut ::encdec::decode::DecodeLimits,
+            ) -> Result<(Self::Output, usize), Self::Error> {
+                use ::encdec::decode::{DecodeSplit, DecodedTagged};
+
+                // Enter a nested object, exiting on drop where decoding fails
+                let mut _scope = _limits.scope()?;
+                let _limits = &mut *_scope;
+
+                let mut _pos = 0;
+
+                #parsers
+
+                drop(_scope);
+
+                // Check trailing bytes once the nested object has been exited
+                #exact
+
+                let _obj = #obj;
+
+                #validate
+
+                Ok((_obj, _pos))
+            }
+        }
+    }
+    .into()
+}
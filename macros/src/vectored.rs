@@ -0,0 +1,122 @@
+//! `#[derive(EncodeVectored)`] macro implementation
+
+use proc_macro::TokenStream;
+
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput};
+
+use crate::{
+    attrs::{FieldAttrs, StructAttrs},
+    fields::{self, FieldEncoder},
+};
+
+/// EncodeVectored derive helper
+pub fn derive_encode_vectored_impl(input: TokenStream) -> TokenStream {
+    let DeriveInput {
+        ident,
+        data,
+        generics,
+        attrs,
+        ..
+    } = parse_macro_input!(input);
+
+    // Extract struct fields
+    let s = match data {
+        Data::Struct(s) => s,
+        _ => panic!("Unsupported object type for derivation"),
+    };
+
+    // Parse struct and field attributes, reporting invalid attributes at their span
+    let struct_attrs = match StructAttrs::parse(attrs.iter()) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let field_attrs = match FieldAttrs::parse_fields(&s.fields) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    // Fetch bounds for generics
+    let (impl_generics, ty_generics, _where_clause) = generics.split_for_impl();
+
+    // Build vectored encoder for each field
+    let mut encoders = quote! {};
+    let mut validators = quote! {};
+
+    s.fields.iter().enumerate().for_each(|(i, f)| {
+        let attrs = &field_attrs[i];
+        let id = fields::member(f, i);
+        let ty = &f.ty;
+
+        // Map field errors for context, offsets are relative to the failing field
+        let field = fields::name(f, i);
+        let map_err = struct_attrs.map_context(&ident, &field);
+
+        let call_len = attrs.call_len(&id);
+
+        let call_encode = match FieldEncoder::new(attrs, ty, &map_err) {
+            // Slice-based overrides, encoded into the scratch buffer
+            FieldEncoder::Override(e) => quote! {
+                _index += vectored.write_with(#call_len, |b| #e(&self.#id, b))#map_err?;
+            },
+            // Sink-based overrides, written to the scratch buffer
+            FieldEncoder::Sink(e) => quote! {
+                _index += #e(&self.#id, vectored)#map_err?;
+            },
+            // `length_of` types filled using length of target field
+            FieldEncoder::LengthOf(n) => quote! {
+                #n
+                _index += vectored.write(&n)#map_err?;
+            },
+            // Normal fields using vectored encode, referencing payloads in place
+            FieldEncoder::Field => quote! {
+                _index += self.#id.encode_vectored(vectored)#map_err?;
+            },
+        };
+
+        // Validate fields prior to encoding where enabled
+        if let (Some(v), true) = (&attrs.validate, struct_attrs.validate_encode) {
+            validators.extend(quote! {
+                #v(&self.#id)#map_err?;
+            });
+        }
+
+        encoders.extend(call_encode);
+    });
+
+    // Validate objects prior to encoding where enabled
+    if let (Some(v), true) = (&struct_attrs.validate, struct_attrs.validate_encode) {
+        validators.extend(quote! {
+            #v(self)?;
+        });
+    }
+
+    // Override error return type if specified
+    let err = struct_attrs.error_type();
+
+    // Add vectored encode and error bounds for Encode types
+    let where_clause = fields::where_clause(
+        &generics,
+        &["Encode"],
+        true,
+        |t, a| quote!(#t: ::encdec::encode::EncodeVectored, #err: From<<#t as #a>::Error>),
+    );
+
+    quote! {
+        impl #impl_generics ::encdec::encode::EncodeVectored for #ident #ty_generics #where_clause {
+            fn encode_vectored<'encdec_v>(&'encdec_v self, vectored: &mut ::encdec::encode::Vectored<'encdec_v>) -> Result<usize, Self::Error> {
+                use ::encdec::Encode;
+                use ::encdec::encode::EncodeVectored;
+
+                #validators
+
+                let mut _index = 0;
+
+                #encoders
+
+                Ok(_index)
+            }
+        }
+    }
+    .into()
+}